@@ -0,0 +1,575 @@
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::sudoku::solver::{solve_batch, BatchOptions, SolveOutcome, SolverAlgorithm};
+use crate::sudoku::{read_all_sudokus_from_file, read_single_sudoku_from_file};
+use crate::sudoku::{GeneratorOptions, Sudoku, SudokuError, Symmetry};
+
+/// Everything went fine: all puzzles were solved, passed the check or were converted.
+pub const EXIT_SUCCESS: u8 = 0;
+/// At least one puzzle could not be solved or did not pass the check.
+pub const EXIT_FAILURE: u8 = 1;
+/// The arguments could not be parsed.
+pub const EXIT_USAGE: u8 = 2;
+/// The input file could not be read, or a line in it is not a sudoku.
+pub const EXIT_INPUT: u8 = 3;
+
+const USAGE: &str = "\
+Usage: sudoku_solver_rust <command> [input] [options]
+
+Commands:
+  solve      Solve the sudoku(s) in the input file and print the solution
+  check      Check whether the sudoku(s) in the input file are solved correctly
+  batch      Solve every sudoku in the input file and print a summary
+  generate   Generate new sudokus with a unique solution
+  rate       Rate the difficulty of the sudoku(s) in the input file
+  convert    Print the sudoku(s) in the input file in another format
+
+Options:
+  -a, --algorithm <name>  simple, most-restricted, hash-map, dancing-links, sat,
+                          propagation or bitboard (default: hash-map)
+      --all               Use every line of the input file instead of only the first
+      --node-limit <n>    Give up on a sudoku after visiting n nodes of the search tree
+  -j, --threads <n>       Number of threads to solve with (default: one per core)
+      --split             Split the search for each sudoku over the threads instead of
+                          solving several sudokus at once, searching most-restricted
+  -f, --format <format>   line or grid (default: grid, line for generate)
+  -n, --count <n>         Number of sudokus to generate (default: 1)
+      --clues <n>         Number of clues to aim for when generating (default: 25)
+      --symmetry <name>   none, rotational or mirror clue layout (default: rotational)
+      --seed <n>          Seed for generating, the same seed gives the same sudokus
+  -h, --help              Print this message
+
+The input defaults to $SUDOKU_FILE_DIR/0.txt, with data/ as the default directory.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subcommand {
+    Solve,
+    Check,
+    Batch,
+    Generate,
+    Rate,
+    Convert,
+    Help,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One sudoku per line as 81 chars.
+    Line,
+    /// Nine lines per sudoku with separators between the blocks.
+    Grid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliOptions {
+    pub subcommand: Subcommand,
+    pub input: String,
+    pub algorithm: SolverAlgorithm,
+    pub all: bool,
+    pub format: OutputFormat,
+    pub node_limit: Option<u64>,
+    pub threads: usize,
+    /// Split the search for one sudoku over the threads, see
+    /// [`Sudoku::solve_in_parallel`].
+    pub split: bool,
+    pub count: usize,
+    pub clues: usize,
+    pub symmetry: Symmetry,
+    pub seed: Option<u64>,
+}
+
+/// The input file used when none is passed on the command line.
+fn default_input() -> String {
+    let mut sudoku_file_dir = env::var("SUDOKU_FILE_DIR").unwrap_or("data/".to_string());
+    sudoku_file_dir.push_str("0.txt");
+    sudoku_file_dir
+}
+
+/// Parse the command line arguments, without the program name.
+pub fn parse_args(args: &[String]) -> Result<CliOptions, String> {
+    let mut args = args.iter();
+    let subcommand = match args.next().map(|s| s.as_str()) {
+        Some("solve") => Subcommand::Solve,
+        Some("check") => Subcommand::Check,
+        Some("batch") => Subcommand::Batch,
+        Some("generate") => Subcommand::Generate,
+        Some("rate") => Subcommand::Rate,
+        Some("convert") => Subcommand::Convert,
+        Some("-h") | Some("--help") | Some("help") => Subcommand::Help,
+        Some(other) => return Err(format!("unknown command '{other}'")),
+        None => return Err("no command given".to_string()),
+    };
+    let mut options = CliOptions {
+        subcommand,
+        input: default_input(),
+        algorithm: SolverAlgorithm::EmptyCellHashMap,
+        all: subcommand == Subcommand::Batch,
+        format: if subcommand == Subcommand::Generate {
+            OutputFormat::Line
+        } else {
+            OutputFormat::Grid
+        },
+        node_limit: None,
+        threads: BatchOptions::default().threads,
+        split: false,
+        count: 1,
+        clues: GeneratorOptions::default().clues,
+        symmetry: GeneratorOptions::default().symmetry,
+        seed: None,
+    };
+    let mut input = None;
+    let mut algorithm_given = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-a" | "--algorithm" => {
+                let name = args.next().ok_or(format!("{arg} needs a value"))?;
+                options.algorithm = name.parse()?;
+                algorithm_given = true;
+            }
+            "-f" | "--format" => {
+                options.format = match args.next().map(|s| s.as_str()) {
+                    Some("line") => OutputFormat::Line,
+                    Some("grid") => OutputFormat::Grid,
+                    Some(other) => return Err(format!("unknown format '{other}'")),
+                    None => return Err(format!("{arg} needs a value")),
+                };
+            }
+            "--all" => options.all = true,
+            "--split" => options.split = true,
+            "--node-limit" => options.node_limit = Some(parse_number(arg, args.next())?),
+            "-j" | "--threads" => options.threads = parse_number(arg, args.next())?,
+            "-n" | "--count" => options.count = parse_number(arg, args.next())?,
+            "--clues" => options.clues = parse_number(arg, args.next())?,
+            "--seed" => options.seed = Some(parse_number(arg, args.next())?),
+            "--symmetry" => {
+                let name = args.next().ok_or(format!("{arg} needs a value"))?;
+                options.symmetry = name.parse()?;
+            }
+            "-h" | "--help" => options.subcommand = Subcommand::Help,
+            flag if flag.starts_with('-') => return Err(format!("unknown option '{flag}'")),
+            path => {
+                if input.is_some() {
+                    return Err(format!("unexpected argument '{path}'"));
+                }
+                input = Some(path.to_string());
+            }
+        }
+    }
+    if let Some(input) = input {
+        options.input = input;
+    }
+    if options.split {
+        if algorithm_given && options.algorithm != SolverAlgorithm::MostRestricted {
+            return Err("--split only searches like most-restricted".to_string());
+        }
+        if options.node_limit.is_some() {
+            return Err("--split can not be combined with --node-limit".to_string());
+        }
+        options.algorithm = SolverAlgorithm::MostRestricted;
+    }
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+    let value = value.ok_or(format!("{flag} needs a value"))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' for {flag}"))
+}
+
+/// Parse the arguments, run the requested command and return the exit code.
+pub fn run(args: &[String]) -> u8 {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return EXIT_USAGE;
+        }
+    };
+    match options.subcommand {
+        Subcommand::Help => {
+            println!("{USAGE}");
+            EXIT_SUCCESS
+        }
+        Subcommand::Generate => run_generate(&options),
+        _ => run_on_input(&options),
+    }
+}
+
+fn read_sudoku_lines(options: &CliOptions) -> Result<Vec<String>, SudokuError> {
+    if options.all {
+        read_all_sudokus_from_file(String::new(), &options.input)
+    } else {
+        read_single_sudoku_from_file(String::new(), &options.input).map(|line| vec![line])
+    }
+}
+
+fn format_sudoku(sudoku: &Sudoku, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Line => sudoku.to_line(),
+        OutputFormat::Grid => sudoku.to_grid(),
+    }
+}
+
+fn print_sudoku(sudoku: &Sudoku, format: OutputFormat) {
+    println!("{}", format_sudoku(sudoku, format));
+}
+
+fn run_generate(options: &CliOptions) -> u8 {
+    let seed = options.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or(0)
+    });
+    for index in 0..options.count {
+        let generator_options = GeneratorOptions {
+            clues: options.clues,
+            symmetry: options.symmetry,
+            seed: seed.wrapping_add(index as u64),
+        };
+        print_sudoku(&Sudoku::generate(&generator_options), options.format);
+    }
+    EXIT_SUCCESS
+}
+
+/// The line `rate` prints for a sudoku.
+fn run_on_input(options: &CliOptions) -> u8 {
+    let (printed, exit_code) = report_on_input(options);
+    for line in printed {
+        println!("{line}");
+    }
+    exit_code
+}
+
+/// Run a command on the sudokus of the input file. Returns what it prints, one entry
+/// per sudoku or summary, and the exit code. Errors are printed right away.
+fn report_on_input(options: &CliOptions) -> (Vec<String>, u8) {
+    let sudoku_lines = match read_sudoku_lines(options) {
+        Ok(sudoku_lines) => sudoku_lines,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return (Vec::new(), EXIT_INPUT);
+        }
+    };
+    let mut printed = Vec::new();
+    let mut failed = 0;
+    let mut invalid = 0;
+    let mut numbers = Vec::with_capacity(sudoku_lines.len());
+    let mut sudokus = Vec::with_capacity(sudoku_lines.len());
+    for (sudoku_number, sudoku_line) in sudoku_lines.iter().enumerate() {
+        // Conflicting values are what `check` is there to report, so it takes any board
+        // that can be read.
+        let parsed = if options.subcommand == Subcommand::Check {
+            Sudoku::parse_board(sudoku_line)
+        } else {
+            Sudoku::create_board(sudoku_line.clone())
+        };
+        match parsed {
+            Ok(sudoku) => {
+                numbers.push(sudoku_number);
+                sudokus.push(sudoku);
+            }
+            Err(error) => {
+                eprintln!("error: {}", error.at_line(sudoku_number + 1));
+                invalid += 1;
+            }
+        }
+    }
+    if matches!(options.subcommand, Subcommand::Solve | Subcommand::Batch) {
+        let solved: Vec<(SolveOutcome, Sudoku)> = if options.split {
+            sudokus
+                .iter()
+                .map(|sudoku| {
+                    let mut sudoku = sudoku.clone();
+                    (sudoku.solve_in_parallel(options.threads), sudoku)
+                })
+                .collect()
+        } else {
+            let batch_options = BatchOptions {
+                algorithm: options.algorithm,
+                threads: options.threads,
+                node_limit: options.node_limit,
+            };
+            solve_batch(&sudokus, &batch_options)
+                .into_iter()
+                .map(|result| (result.outcome, result.sudoku))
+                .collect()
+        };
+        for (index, (outcome, solution)) in solved.into_iter().enumerate() {
+            let sudoku_number = numbers[index];
+            match outcome {
+                SolveOutcome::Solved if solution.check_sudoku_completed() => {
+                    if options.subcommand == Subcommand::Solve {
+                        printed.push(format_sudoku(&solution, options.format));
+                    }
+                }
+                SolveOutcome::Solved => {
+                    eprintln!("Sudoku {sudoku_number} not solved correctly");
+                    failed += 1;
+                }
+                SolveOutcome::Unsolvable => {
+                    eprintln!("Sudoku {sudoku_number} has no solution");
+                    failed += 1;
+                }
+                SolveOutcome::Aborted => {
+                    eprintln!("Sudoku {sudoku_number} hit the node limit");
+                    failed += 1;
+                }
+            }
+        }
+    }
+    for (sudoku_number, sudoku) in numbers.into_iter().zip(sudokus.iter()) {
+        match options.subcommand {
+            Subcommand::Check => {
+                if sudoku.check_sudoku_completed() {
+                    printed.push(format!("Sudoku {sudoku_number} is solved correctly"));
+                } else {
+                    printed.push(format!("Sudoku {sudoku_number} is not solved correctly"));
+                    failed += 1;
+                }
+            }
+            Subcommand::Rate => {
+                printed.push(format!("Sudoku {sudoku_number}: {}", sudoku.rate()));
+            }
+            Subcommand::Convert => printed.push(format_sudoku(sudoku, options.format)),
+            Subcommand::Solve | Subcommand::Batch => {}
+            Subcommand::Generate | Subcommand::Help => unreachable!(),
+        }
+    }
+    if options.subcommand == Subcommand::Batch {
+        printed.push(format!(
+            "Solved {} of {} sudokus with {}",
+            sudoku_lines.len() - failed - invalid,
+            sudoku_lines.len(),
+            options.algorithm.name()
+        ));
+    }
+    let exit_code = if invalid > 0 {
+        EXIT_INPUT
+    } else if failed > 0 {
+        EXIT_FAILURE
+    } else {
+        EXIT_SUCCESS
+    };
+    (printed, exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_solve_with_options() {
+        let options = parse_args(&args(&[
+            "solve",
+            "puzzles.txt",
+            "--algorithm",
+            "simple",
+            "--all",
+            "-f",
+            "line",
+            "--node-limit",
+            "1000",
+            "-j",
+            "4",
+        ]))
+        .unwrap();
+        assert_eq!(options.subcommand, Subcommand::Solve);
+        assert_eq!(options.input, "puzzles.txt");
+        assert_eq!(options.algorithm, SolverAlgorithm::Simple);
+        assert!(options.all);
+        assert_eq!(options.format, OutputFormat::Line);
+        assert_eq!(options.node_limit, Some(1000));
+        assert_eq!(options.threads, 4);
+    }
+
+    #[test]
+    fn batch_uses_all_lines_by_default() {
+        let options = parse_args(&args(&["batch", "puzzles.txt"])).unwrap();
+        assert!(options.all);
+        assert_eq!(options.algorithm, SolverAlgorithm::EmptyCellHashMap);
+    }
+
+    #[test]
+    fn parse_generate_options() {
+        let options = parse_args(&args(&[
+            "generate",
+            "--count",
+            "3",
+            "--clues",
+            "30",
+            "--symmetry",
+            "mirror",
+            "--seed",
+            "12",
+        ]))
+        .unwrap();
+        assert_eq!(options.subcommand, Subcommand::Generate);
+        assert_eq!(options.format, OutputFormat::Line);
+        assert_eq!(options.count, 3);
+        assert_eq!(options.clues, 30);
+        assert_eq!(options.symmetry, Symmetry::Mirror);
+        assert_eq!(options.seed, Some(12));
+    }
+
+    #[test]
+    fn reject_unknown_arguments() {
+        assert!(parse_args(&args(&[])).is_err());
+        assert!(parse_args(&args(&["frobnicate"])).is_err());
+        assert!(parse_args(&args(&["solve", "--algorithm", "magic"])).is_err());
+        assert!(parse_args(&args(&["solve", "--format"])).is_err());
+        assert!(parse_args(&args(&["solve", "a.txt", "b.txt"])).is_err());
+        assert!(parse_args(&args(&["solve", "--node-limit", "-1"])).is_err());
+        assert!(parse_args(&args(&["batch", "--threads", "many"])).is_err());
+        assert!(parse_args(&args(&["solve", "--split", "-a", "dlx"])).is_err());
+        assert!(parse_args(&args(&["solve", "--split", "--node-limit", "10"])).is_err());
+        assert!(parse_args(&args(&["generate", "--symmetry", "diagonal"])).is_err());
+    }
+
+    #[test]
+    fn missing_input_file_is_an_input_error() {
+        assert_eq!(run(&args(&["solve", "does/not/exist.txt"])), EXIT_INPUT);
+    }
+
+    #[test]
+    fn exit_codes_of_check_and_convert() {
+        let directory = std::env::temp_dir().to_string_lossy().to_string();
+        let write = |name: &str, content: &str| {
+            let path = format!("{directory}/sudoku_solver_rust_{name}.txt");
+            std::fs::write(&path, content).unwrap();
+            path
+        };
+        let solved = write(
+            "solved",
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179\n",
+        );
+        let wrong = write(
+            "wrong",
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286197\n",
+        );
+        let puzzle = write(
+            "puzzle",
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079\n",
+        );
+        let invalid = write("invalid", "53007000060019500009800006\n");
+
+        assert_eq!(run(&args(&["check", &solved])), EXIT_SUCCESS);
+        assert_eq!(run(&args(&["check", &wrong])), EXIT_FAILURE);
+        assert_eq!(run(&args(&["check", &puzzle])), EXIT_FAILURE);
+        assert_eq!(run(&args(&["check", &invalid])), EXIT_INPUT);
+
+        assert_eq!(run(&args(&["convert", &puzzle])), EXIT_SUCCESS);
+        assert_eq!(run(&args(&["convert", &wrong])), EXIT_INPUT);
+        assert_eq!(run(&args(&["convert", &invalid])), EXIT_INPUT);
+        let options = parse_args(&args(&["convert", &puzzle, "-f", "line"])).unwrap();
+        assert_eq!(
+            report_on_input(&options),
+            (
+                vec![
+                    "530070000600195000098000060800060003400803001700020006060000280000419005000080079"
+                        .to_string()
+                ],
+                EXIT_SUCCESS
+            )
+        );
+    }
+
+    #[test]
+    fn rate_every_line_of_a_file() {
+        let mut input = std::env::temp_dir().to_string_lossy().to_string();
+        input.push_str("/sudoku_solver_rust_rate.txt");
+        std::fs::write(
+            &input,
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079\n\
+             478600000000900006000804000500037000030040080004000120090000062000000700200700010\n\
+             800000000003600000070090200050007000000045700000100030001000068008500010090000400\n",
+        )
+        .unwrap();
+        let options = parse_args(&args(&["rate", &input, "--all"])).unwrap();
+        let (printed, exit_code) = report_on_input(&options);
+        assert_eq!(exit_code, EXIT_SUCCESS);
+        assert_eq!(
+            printed,
+            [
+                "Sudoku 0: 1.5 easy (Hidden Single), total 76.5",
+                "Sudoku 1: 3.4 medium (Hidden Pair), total 112.3",
+                "Sudoku 2: 10.0 extreme (not solved), total 10.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn check_a_wrongly_filled_board() {
+        let mut input = std::env::temp_dir().to_string_lossy().to_string();
+        input.push_str("/sudoku_solver_rust_check_wrong.txt");
+        // Solved, except that the last two cells are swapped.
+        std::fs::write(
+            &input,
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179\n\
+             534678912672195348198342567859761423426853791713924856961537284287419635345286197\n",
+        )
+        .unwrap();
+        let options = parse_args(&args(&["check", &input, "--all"])).unwrap();
+        let (printed, exit_code) = report_on_input(&options);
+        assert_eq!(exit_code, EXIT_FAILURE);
+        assert_eq!(
+            printed,
+            [
+                "Sudoku 0 is solved correctly",
+                "Sudoku 1 is not solved correctly",
+            ]
+        );
+        assert_eq!(run(&args(&["check", &input, "--all"])), EXIT_FAILURE);
+        assert_eq!(run(&args(&["solve", &input, "--all"])), EXIT_INPUT);
+    }
+
+    #[test]
+    fn batch_on_several_threads() {
+        let mut input = std::env::temp_dir().to_string_lossy().to_string();
+        input.push_str("/sudoku_solver_rust_batch.txt");
+        std::fs::write(
+            &input,
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079\n\
+             800000000003600000070090200050007000000045700000100030001000068008500010090000400\n\
+             012345678900000000000000000000000000000000000000000000000000000000000000000000000\n",
+        )
+        .unwrap();
+        assert_eq!(run(&args(&["batch", &input, "-j", "2"])), EXIT_FAILURE);
+        assert_eq!(
+            run(&args(&["solve", &input, "--threads", "3", "-f", "line"])),
+            EXIT_SUCCESS
+        );
+    }
+
+    #[test]
+    fn split_the_search_for_one_sudoku() {
+        let options = parse_args(&args(&["solve", "puzzles.txt", "--split", "-j", "4"])).unwrap();
+        assert!(options.split);
+        assert_eq!(options.threads, 4);
+        assert_eq!(options.algorithm, SolverAlgorithm::MostRestricted);
+
+        let mut input = std::env::temp_dir().to_string_lossy().to_string();
+        input.push_str("/sudoku_solver_rust_split.txt");
+        std::fs::write(
+            &input,
+            "800000000003600000070090200050007000000045700000100030001000068008500010090000400\n\
+             012345678900000000000000000000000000000000000000000000000000000000000000000000000\n",
+        )
+        .unwrap();
+        assert_eq!(
+            run(&args(&["solve", &input, "--split", "-j", "4"])),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run(&args(&["batch", &input, "--split", "--threads", "2"])),
+            EXIT_FAILURE
+        );
+    }
+}
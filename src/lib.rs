@@ -1,35 +1,39 @@
-use crate::sudoku::{
-    Sudoku, read_all_sudokus_from_file, read_single_sudoku_from_file,
-};
-use crate::sudoku::solver::SolverAlgorithm;
-
-mod sudoku;
+//! A sudoku solver.
+//!
+//! Boards are created from a line of 81 chars, read row by row, where `0` marks an
+//! empty cell. They can be solved with any of the [`SolverAlgorithm`]s and checked
+//! afterwards.
+//!
+//! ```
+//! use sudoku_solver_rust::{SolveOutcome, SolverAlgorithm, Sudoku};
+//!
+//! let mut sudoku = Sudoku::create_board(
+//!     "530070000600195000098000060800060003400803001700020006060000280000419005000080079"
+//!         .to_string(),
+//! )
+//! .unwrap();
+//! let outcome = sudoku.solve_sudoku(&SolverAlgorithm::MostRestricted);
+//! assert_eq!(outcome, SolveOutcome::Solved);
+//! assert!(sudoku.check_sudoku_completed());
+//! assert_eq!(sudoku.to_array()[0], [5, 3, 4, 6, 7, 8, 9, 1, 2]);
+//! ```
 
-pub fn run(sudoku_file_dir: String, sudoku_file_name: String) {
-    let all = true;
-    let algorithm = SolverAlgorithm::EmptyCellHashMap;
+mod cli;
+pub mod sudoku;
 
-    if all {
-        let sudoku_lines = read_all_sudokus_from_file(sudoku_file_dir, &sudoku_file_name).unwrap();
-        for (sudoku_number, sudoku_line) in sudoku_lines.iter().enumerate() {
-            let mut sudoku = Sudoku::create_board(sudoku_line.clone()).unwrap();
-            sudoku.solve_sudoku(&algorithm);
-            if !sudoku.check_sudoku_completed() {
-                println!("Solving sudoku {sudoku_number}");
-                println!("Sudoku not solved correctly")
-            }
+pub use sudoku::logic::{
+    AlmostLockedSet, Candidate, CandidateGrid, Chain, Derivation, Difficulty, Hint, HintLevel,
+    House, InferenceGraph, LogicalSolution, Rating, Step, StepDetail, Technique,
+};
+pub use sudoku::sat::{parse_dimacs_model, CdclSolver, Cnf};
+pub use sudoku::solver::{
+    solve_batch, BatchOptions, BatchResult, SolveOutcome, Solutions, SolverAlgorithm, Uniqueness,
+};
+pub use sudoku::{read_all_sudokus_from_file, read_single_sudoku_from_file};
+pub use sudoku::{GeneratorOptions, Sudoku, SudokuError, Symmetry};
 
-        }
-    } else {
-        let sudoku_line = read_single_sudoku_from_file(sudoku_file_dir, &sudoku_file_name).unwrap();
-        let mut sudoku = Sudoku::create_board(sudoku_line).unwrap();
-        sudoku.display_sudoku_board();
-        sudoku.solve_sudoku(&algorithm);
-        if sudoku.check_sudoku_completed() {
-            println!("Sudoku solved correctly!");
-        } else {
-            println!("Did not solve sudoku correctly");
-        }
-        sudoku.display_sudoku_board();
-    }
+/// Run the command line interface with the given arguments, without the program name,
+/// and return the exit code.
+pub fn run(args: &[String]) -> u8 {
+    cli::run(args)
 }
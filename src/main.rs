@@ -1,8 +1,8 @@
 use std::env;
+use std::process::ExitCode;
 use sudoku_solver_rust::run;
 
-fn main() {
-    let sudoku_file_dir: String = env::var("SUDOKU_FILE_DIR").unwrap_or("data/".to_string());
-    let sudoku_file_name = "0.txt".to_string();
-    run(sudoku_file_dir, sudoku_file_name);
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    ExitCode::from(run(&args))
 }
@@ -2,36 +2,41 @@ use crate::sudoku::Sudoku;
 
 impl Sudoku {
     fn check_block_correctness(&self, index: usize) -> bool {
-        let mut block = Vec::new();
-        for i in 0..9 {
-            block.push(self.board[i % 3 + (index / 3) * 3][i / 3 + (index % 3) * 3]);
+        let mut block = [0; 9];
+        for (i, value) in block.iter_mut().enumerate() {
+            *value = self.digit(i % 3 + (index / 3) * 3, i / 3 + (index % 3) * 3);
         }
         let dups = Sudoku::check_duplicates_in_slice(&block);
         !dups
     }
     fn check_row_correctness(&self, index: usize) -> bool {
-        let row = &self.board[index];
-        let dups = Sudoku::check_duplicates_in_slice(&row);
+        let row = &self.board[index * 9..index * 9 + 9];
+        let dups = Sudoku::check_duplicates_in_slice(row);
         !dups
     }
     fn check_column_correctness(&self, index: usize) -> bool {
-        let mut column = Vec::new();
-        for i in 0..self.board.len() {
-            column.push(self.board[i][index]);
+        let mut column = [0; 9];
+        for (i, value) in column.iter_mut().enumerate() {
+            *value = self.digit(i, index);
         }
         let dups = Sudoku::check_duplicates_in_slice(&column);
         !dups
     }
-    fn check_duplicates_in_slice(slice: &Vec<char>) -> bool {
-        let slice: Vec<char> = slice.iter().filter(|s| **s != '0').map(|s| *s).collect();
-        for i in 1..slice.len() {
-            if slice[i..].contains(&slice[i - 1]) {
+    /// Check whether a digit occurs more than once, ignoring empty cells.
+    fn check_duplicates_in_slice(slice: &[u8]) -> bool {
+        let mut seen: u16 = 0;
+        for digit in slice.iter().filter(|digit| **digit != 0) {
+            let bit = 1 << (digit - 1);
+            if seen & bit != 0 {
                 return true;
             }
+            seen |= bit;
         }
         false
     }
 
+    /// Check that no row, column or block contains the same value twice. Empty cells
+    /// are ignored, so this also holds for a partially filled board.
     pub fn check_correctness_of_sudoku(&self) -> bool {
         for index in 0..9 {
             if !self.check_row_correctness(index) {
@@ -47,15 +52,12 @@ impl Sudoku {
         true
     }
 
+    /// Check that the board has no empty cells left.
     pub fn check_sudoku_is_filled(&self) -> bool {
-        for row in self.board.iter() {
-            if row.contains(&'0') {
-                return false;
-            }
-        }
-        true
+        !self.board.contains(&0)
     }
 
+    /// Check that the board is filled and correct, i.e. solved.
     pub fn check_sudoku_completed(&self) -> bool {
         self.check_sudoku_is_filled() && self.check_correctness_of_sudoku()
     }
@@ -67,19 +69,19 @@ mod tests {
 
     #[test]
     fn confirm_find_duplicates() {
-        let vec_with_duplicates = vec!['a', 'b', 'b'];
+        let vec_with_duplicates = vec![1, 2, 2];
         assert!(Sudoku::check_duplicates_in_slice(&vec_with_duplicates));
     }
 
     #[test]
     fn confirm_no_duplicates() {
-        let vec_without_duplicates = vec!['a', 'b', 'c'];
-        assert_eq!(Sudoku::check_duplicates_in_slice(&vec_without_duplicates), false);
+        let vec_without_duplicates = vec![1, 2, 3, 0, 0];
+        assert!(!Sudoku::check_duplicates_in_slice(&vec_without_duplicates));
     }
 
     #[test]
     fn row_not_correct() {
-        let sudoku = Sudoku::create_board(
+        let sudoku = Sudoku::parse_board(
             "123456788\
              000000000\
              000000000\
@@ -88,11 +90,10 @@ mod tests {
              000000000\
              000000000\
              000000000\
-             000000000"
-                .to_string(),
+             000000000",
         )
         .unwrap();
-        assert_eq!(Sudoku::check_row_correctness(&sudoku, 0), false);
+        assert!(!Sudoku::check_row_correctness(&sudoku, 0));
     }
 
     #[test]
@@ -115,7 +116,7 @@ mod tests {
 
     #[test]
     fn column_not_correct() {
-        let sudoku = Sudoku::create_board(
+        let sudoku = Sudoku::parse_board(
             "100000000\
              200000000\
              300000000\
@@ -124,11 +125,10 @@ mod tests {
              600000000\
              700000000\
              800000000\
-             800000000"
-                .to_string(),
+             800000000",
         )
         .unwrap();
-        assert_eq!(Sudoku::check_column_correctness(&sudoku, 0), false);
+        assert!(!Sudoku::check_column_correctness(&sudoku, 0));
     }
 
     #[test]
@@ -151,7 +151,7 @@ mod tests {
 
     #[test]
     fn block_not_correct() {
-        let sudoku = Sudoku::create_board(
+        let sudoku = Sudoku::parse_board(
             "123000000\
              456000000\
              788000000\
@@ -160,11 +160,10 @@ mod tests {
              000000000\
              000000000\
              000000000\
-             000000000"
-                .to_string(),
+             000000000",
         )
         .unwrap();
-        assert_eq!(Sudoku::check_block_correctness(&sudoku, 0), false);
+        assert!(!Sudoku::check_block_correctness(&sudoku, 0));
     }
 
     #[test]
@@ -0,0 +1,92 @@
+use std::error::Error;
+use std::fmt;
+
+/// Everything that can go wrong while reading, creating or solving a sudoku.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SudokuError {
+    /// The file with sudokus does not exist.
+    FileNotFound(String),
+    /// The file exists but could not be read.
+    UnreadableFile { path: String, reason: String },
+    /// The file does not contain any sudoku.
+    EmptyFile(String),
+    /// A sudoku line does not contain exactly 81 cells.
+    InvalidLineLength { line: usize, length: usize },
+    /// A sudoku line contains a char that is not a cell value. The column counts chars
+    /// from 1.
+    InvalidCharacter {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+    /// The givens of a sudoku line already contain the same value twice in a row,
+    /// column or block.
+    ConflictingGivens { line: usize },
+    /// A cell value passed as a number is larger than 9.
+    InvalidValue {
+        row: usize,
+        column: usize,
+        value: u8,
+    },
+    /// The output of a SAT solver is not a model of a sudoku formula.
+    InvalidModel(String),
+}
+
+impl SudokuError {
+    /// Move an error about a sudoku line to another line of the input, since a single
+    /// line on its own is always reported as line 1.
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            SudokuError::InvalidLineLength { length, .. } => {
+                SudokuError::InvalidLineLength { line, length }
+            }
+            SudokuError::InvalidCharacter {
+                column, character, ..
+            } => SudokuError::InvalidCharacter {
+                line,
+                column,
+                character,
+            },
+            SudokuError::ConflictingGivens { .. } => SudokuError::ConflictingGivens { line },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for SudokuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SudokuError::FileNotFound(path) => write!(f, "file '{path}' does not exist"),
+            SudokuError::UnreadableFile { path, reason } => {
+                write!(f, "cannot read '{path}': {reason}")
+            }
+            SudokuError::EmptyFile(path) => write!(f, "file '{path}' contains no sudoku"),
+            SudokuError::InvalidLineLength { line, length } => {
+                write!(f, "line {line} has {length} cells instead of 81")
+            }
+            SudokuError::InvalidCharacter {
+                line,
+                column,
+                character,
+            } => write!(
+                f,
+                "line {line}, column {column}: '{character}' is not a cell value"
+            ),
+            SudokuError::ConflictingGivens { line } => {
+                write!(
+                    f,
+                    "line {line} has the same value twice in a row, column or block"
+                )
+            }
+            SudokuError::InvalidValue { row, column, value } => {
+                write!(
+                    f,
+                    "cell ({row}, {column}) has value {value}, expected 0 to 9"
+                )
+            }
+            SudokuError::InvalidModel(reason) => write!(f, "invalid DIMACS model: {reason}"),
+        }
+    }
+}
+
+impl Error for SudokuError {}
@@ -0,0 +1,220 @@
+use std::str::FromStr;
+
+use crate::sudoku::{digits, Sudoku};
+
+/// Which cells are emptied together, so the clues form a symmetric pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Every cell is emptied on its own.
+    None,
+    /// A cell is emptied together with the cell rotated 180 degrees around the centre.
+    Rotational,
+    /// A cell is emptied together with the cell mirrored in the middle column.
+    Mirror,
+}
+
+impl Symmetry {
+    /// The cells that are emptied together with the given cell, including itself.
+    fn partners(&self, row: usize, column: usize) -> Vec<(usize, usize)> {
+        let partner = match self {
+            Symmetry::None => (row, column),
+            Symmetry::Rotational => (8 - row, 8 - column),
+            Symmetry::Mirror => (row, 8 - column),
+        };
+        if partner == (row, column) {
+            vec![(row, column)]
+        } else {
+            vec![(row, column), partner]
+        }
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "none" => Ok(Symmetry::None),
+            "rotational" => Ok(Symmetry::Rotational),
+            "mirror" => Ok(Symmetry::Mirror),
+            _ => Err(format!(
+                "unknown symmetry '{name}', expected one of: none, rotational, mirror"
+            )),
+        }
+    }
+}
+
+/// Settings for [`Sudoku::generate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GeneratorOptions {
+    /// The number of clues to stop at. If no more clues can be removed without losing
+    /// the unique solution, the sudoku keeps more clues than this.
+    pub clues: usize,
+    pub symmetry: Symmetry,
+    /// The same seed and settings always produce the same sudoku.
+    pub seed: u64,
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions {
+            clues: 25,
+            symmetry: Symmetry::Rotational,
+            seed: 0,
+        }
+    }
+}
+
+/// A small, seedable pseudo random number generator (SplitMix64), good enough to shuffle
+/// cells and values.
+pub(crate) struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        SeededRng { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Shuffle a slice in place with the Fisher-Yates algorithm.
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next_u64() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+impl Sudoku {
+    /// Generate a new sudoku with exactly one solution. A random full grid is built
+    /// first, after which clues are removed in random order, together with their
+    /// symmetric partners, as long as the solution stays unique.
+    pub fn generate(options: &GeneratorOptions) -> Sudoku {
+        let mut rng = SeededRng::new(options.seed);
+        let mut sudoku = Sudoku::create_board("0".repeat(81)).unwrap();
+        sudoku.fill_randomly(&mut rng);
+
+        let mut cells: Vec<(usize, usize)> = (0..81).map(|index| (index / 9, index % 9)).collect();
+        rng.shuffle(&mut cells);
+        let mut clues = 81;
+        for (row, column) in cells {
+            if sudoku.digit(row, column) == 0 {
+                continue;
+            }
+            let partners = options.symmetry.partners(row, column);
+            if clues < options.clues + partners.len() {
+                continue;
+            }
+            let removed: Vec<u8> = partners
+                .iter()
+                .map(|(row, column)| sudoku.digit(*row, *column))
+                .collect();
+            for (row, column) in partners.iter() {
+                sudoku.unplace(*row, *column);
+            }
+            if sudoku.has_unique_solution() {
+                clues -= partners.len();
+            } else {
+                for ((row, column), digit) in partners.iter().zip(removed) {
+                    sudoku.place(*row, *column, digit);
+                }
+            }
+        }
+        sudoku
+    }
+
+    /// Fill the board with a random solution, like the most restricted cell first solver
+    /// but trying the options of each cell in a random order.
+    fn fill_randomly(&mut self, rng: &mut SeededRng) -> bool {
+        if self.check_sudoku_is_filled() {
+            return true;
+        }
+        if let Ok((row_index, column_index, options)) = self.choose_most_restricted_value() {
+            let mut options: Vec<u8> = digits(options).collect();
+            rng.shuffle(&mut options);
+            for option in options {
+                self.place(row_index, column_index, option);
+                if self.fill_randomly(rng) {
+                    return true;
+                }
+                self.unplace(row_index, column_index);
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::generator::{GeneratorOptions, Symmetry};
+    use crate::sudoku::Sudoku;
+
+    fn clue_count(sudoku: &Sudoku) -> usize {
+        sudoku.to_line().chars().filter(|c| *c != '0').count()
+    }
+
+    #[test]
+    fn generate_unique_sudoku() {
+        let options = GeneratorOptions {
+            clues: 28,
+            symmetry: Symmetry::None,
+            seed: 7,
+        };
+        let sudoku = Sudoku::generate(&options);
+        assert!(sudoku.has_unique_solution());
+        assert!(clue_count(&sudoku) >= 28);
+        assert!(clue_count(&sudoku) < 40);
+        assert_eq!(
+            Sudoku::create_board(sudoku.to_line()).unwrap(),
+            sudoku,
+            "the output can be read back as a line"
+        );
+    }
+
+    #[test]
+    fn same_seed_gives_same_sudoku() {
+        let options = GeneratorOptions {
+            seed: 42,
+            ..GeneratorOptions::default()
+        };
+        assert_eq!(Sudoku::generate(&options), Sudoku::generate(&options));
+        let other_seed = GeneratorOptions {
+            seed: 43,
+            ..GeneratorOptions::default()
+        };
+        assert_ne!(Sudoku::generate(&options), Sudoku::generate(&other_seed));
+    }
+
+    #[test]
+    fn keep_clues_symmetric() {
+        for symmetry in [Symmetry::Rotational, Symmetry::Mirror] {
+            let options = GeneratorOptions {
+                clues: 30,
+                symmetry,
+                seed: 3,
+            };
+            let sudoku = Sudoku::generate(&options);
+            assert!(sudoku.has_unique_solution());
+            for row in 0..9 {
+                for column in 0..9 {
+                    let (partner_row, partner_column) = match symmetry {
+                        Symmetry::Rotational => (8 - row, 8 - column),
+                        _ => (row, 8 - column),
+                    };
+                    assert_eq!(
+                        sudoku.value(row, column) == '0',
+                        sudoku.value(partner_row, partner_column) == '0'
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,387 @@
+use std::fmt;
+
+use crate::sudoku::digits;
+use crate::sudoku::logic::{
+    cell_name, combinations, sees, Candidate, CandidateGrid, House, Step, StepDetail, Technique,
+};
+
+/// An almost locked set: `n` cells of one house with `n + 1` candidates between them.
+/// Removing any one of those digits leaves a locked set, in which every remaining digit
+/// has to be placed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlmostLockedSet {
+    pub house: House,
+    pub cells: Vec<usize>,
+    /// Bit mask of the candidates of the cells.
+    pub candidates: u16,
+}
+
+impl AlmostLockedSet {
+    /// Bit set of the cells, for quick overlap tests.
+    fn cell_bits(&self) -> u128 {
+        self.cells.iter().fold(0, |bits, cell| bits | 1 << cell)
+    }
+
+    fn has_digit(&self, digit: u8) -> bool {
+        self.candidates & (1 << (digit - 1)) != 0
+    }
+}
+
+/// The cells and the candidates, as in `r1c1,r1c5 (125)`.
+impl fmt::Display for AlmostLockedSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<String> = self.cells.iter().map(|cell| cell_name(*cell)).collect();
+        let candidates: String = digits(self.candidates)
+            .map(|digit| (b'0' + digit) as char)
+            .collect();
+        write!(f, "{} ({candidates})", cells.join(","))
+    }
+}
+
+fn als_step(
+    technique: Technique,
+    sets: Vec<AlmostLockedSet>,
+    restricted_commons: Vec<u8>,
+    stem: Option<usize>,
+    eliminations: Vec<Candidate>,
+) -> Step {
+    let mut cells: Vec<usize> = stem.into_iter().collect();
+    for cell in sets.iter().flat_map(|set| set.cells.iter()) {
+        if !cells.contains(cell) {
+            cells.push(*cell);
+        }
+    }
+    Step {
+        technique,
+        cells,
+        houses: sets.iter().map(|set| set.house).collect(),
+        placements: Vec::new(),
+        eliminations,
+        detail: StepDetail::AlmostLockedSets {
+            sets,
+            restricted_commons,
+            stem,
+        },
+    }
+}
+
+impl CandidateGrid {
+    /// Every almost locked set in the rows, columns and blocks. A set of cells that lies
+    /// in two houses, like two cells of a row in one block, is listed once.
+    pub fn almost_locked_sets(&self) -> Vec<AlmostLockedSet> {
+        let mut sets: Vec<AlmostLockedSet> = Vec::new();
+        for house in House::all() {
+            let open: Vec<usize> = house
+                .cells()
+                .into_iter()
+                .filter(|cell| self.candidates(*cell) != 0)
+                .collect();
+            for size in 1..open.len() {
+                for cells in combinations(&open, size) {
+                    let candidates = cells
+                        .iter()
+                        .fold(0, |union, cell| union | self.candidates(*cell));
+                    if candidates.count_ones() as usize == size + 1
+                        && !sets.iter().any(|set| set.cells == cells)
+                    {
+                        sets.push(AlmostLockedSet {
+                            house,
+                            cells,
+                            candidates,
+                        });
+                    }
+                }
+            }
+        }
+        sets
+    }
+
+    /// The cells of a set that hold the digit as a candidate.
+    fn holders(&self, sets: &[&AlmostLockedSet], digit: u8) -> Vec<usize> {
+        sets.iter()
+            .flat_map(|set| set.cells.iter().copied())
+            .filter(|cell| self.has_candidate(*cell, digit))
+            .collect()
+    }
+
+    /// Whether the digit is a restricted common of two sets that do not overlap: it is in
+    /// both and every cell holding it in one sees every cell holding it in the other, so
+    /// at most one of the sets can contain it.
+    fn is_restricted_common(
+        &self,
+        first: &AlmostLockedSet,
+        second: &AlmostLockedSet,
+        digit: u8,
+    ) -> bool {
+        if !first.has_digit(digit) || !second.has_digit(digit) {
+            return false;
+        }
+        let second_holders = self.holders(&[second], digit);
+        self.holders(&[first], digit)
+            .iter()
+            .all(|cell| second_holders.iter().all(|other| sees(*cell, *other)))
+    }
+
+    /// Two almost locked sets A and B with a restricted common x: one of them loses x and
+    /// becomes locked, so a digit z in both has to be placed in A or in B, and can be
+    /// removed from every cell that sees all the z in both sets.
+    pub(crate) fn find_als_xz(&self) -> Option<Step> {
+        let sets = self.almost_locked_sets();
+        for pair in combinations(&(0..sets.len()).collect::<Vec<usize>>(), 2) {
+            let (first, second) = (&sets[pair[0]], &sets[pair[1]]);
+            if first.cell_bits() & second.cell_bits() != 0 {
+                continue;
+            }
+            let common = first.candidates & second.candidates;
+            for restricted in digits(common) {
+                if !self.is_restricted_common(first, second, restricted) {
+                    continue;
+                }
+                for digit in digits(common).filter(|digit| *digit != restricted) {
+                    let holders = self.holders(&[first, second], digit);
+                    let eliminations = self.candidates_seeing_all(&holders, digit);
+                    if !eliminations.is_empty() {
+                        return Some(als_step(
+                            Technique::AlsXz,
+                            vec![first.clone(), second.clone()],
+                            vec![restricted],
+                            None,
+                            eliminations,
+                        ));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Almost locked sets A and B that each share a different restricted common, x and
+    /// y, with a third set C. C can not lose both, so A or B becomes locked and a digit z
+    /// in both A and B can be removed from every cell that sees all the z in them.
+    pub(crate) fn find_als_xy_wing(&self) -> Option<Step> {
+        let sets = self.almost_locked_sets();
+        for (pivot_index, pivot) in sets.iter().enumerate() {
+            // The sets that share a restricted common with the pivot, with that digit.
+            let mut wings: Vec<(usize, u8)> = Vec::new();
+            for (index, set) in sets.iter().enumerate() {
+                if index == pivot_index || set.cell_bits() & pivot.cell_bits() != 0 {
+                    continue;
+                }
+                for digit in digits(set.candidates & pivot.candidates) {
+                    if self.is_restricted_common(pivot, set, digit) {
+                        wings.push((index, digit));
+                    }
+                }
+            }
+            for pair in combinations(&wings, 2) {
+                let ((first_index, x), (second_index, y)) = (pair[0], pair[1]);
+                let (first, second) = (&sets[first_index], &sets[second_index]);
+                if x == y || first_index == second_index {
+                    continue;
+                }
+                if first.cell_bits() & second.cell_bits() != 0 {
+                    continue;
+                }
+                let common = first.candidates & second.candidates;
+                for digit in digits(common).filter(|digit| *digit != x && *digit != y) {
+                    let holders = self.holders(&[first, second], digit);
+                    let eliminations = self.candidates_seeing_all(&holders, digit);
+                    if !eliminations.is_empty() {
+                        return Some(als_step(
+                            Technique::AlsXyWing,
+                            vec![first.clone(), second.clone(), pivot.clone()],
+                            vec![x, y],
+                            None,
+                            eliminations,
+                        ));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// A stem cell with a petal for each of its candidates: an almost locked set holding
+    /// that digit only in cells that see the stem. Whatever the stem becomes, its petal
+    /// loses the digit and becomes locked, so a digit z in every petal is placed in one of
+    /// them and can be removed from every cell that sees all the z in the petals.
+    pub(crate) fn find_death_blossom(&self) -> Option<Step> {
+        let sets = self.almost_locked_sets();
+        for stem in (0..81).filter(|cell| (2..=3).contains(&self.candidates(*cell).count_ones())) {
+            let stem_digits: Vec<u8> = digits(self.candidates(stem)).collect();
+            let petals: Vec<Vec<&AlmostLockedSet>> = stem_digits
+                .iter()
+                .map(|digit| {
+                    sets.iter()
+                        .filter(|set| !set.cells.contains(&stem) && set.has_digit(*digit))
+                        .filter(|set| {
+                            self.holders(&[set], *digit)
+                                .iter()
+                                .all(|cell| sees(*cell, stem))
+                        })
+                        .collect()
+                })
+                .collect();
+            for digit in 1..=9 {
+                let mut chosen = Vec::new();
+                if let Some(eliminations) = self.grow_blossom(stem, digit, &petals, &mut chosen, 0)
+                {
+                    return Some(als_step(
+                        Technique::DeathBlossom,
+                        chosen.into_iter().cloned().collect(),
+                        stem_digits,
+                        Some(stem),
+                        eliminations,
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    /// Pick a petal for each candidate of the stem in turn, each holding the digit and not
+    /// overlapping the others, as long as some cell still sees every z in the petals.
+    fn grow_blossom<'a>(
+        &self,
+        stem: usize,
+        digit: u8,
+        petals: &[Vec<&'a AlmostLockedSet>],
+        chosen: &mut Vec<&'a AlmostLockedSet>,
+        used: u128,
+    ) -> Option<Vec<Candidate>> {
+        let mut holders = self.holders(chosen, digit);
+        if self.has_candidate(stem, digit) {
+            holders.push(stem);
+        }
+        let eliminations = self.candidates_seeing_all(&holders, digit);
+        if eliminations.is_empty() {
+            return None;
+        }
+        if chosen.len() == petals.len() {
+            return Some(eliminations);
+        }
+        for petal in petals[chosen.len()].iter() {
+            if !petal.has_digit(digit) || petal.cell_bits() & used != 0 {
+                continue;
+            }
+            chosen.push(petal);
+            let found = self.grow_blossom(stem, digit, petals, chosen, used | petal.cell_bits());
+            if found.is_some() {
+                return found;
+            }
+            chosen.pop();
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::logic::test_support::{assert_steps_agree_with_backtracking, grid_with};
+    use crate::sudoku::logic::{cell_index, Candidate, StepDetail, Technique};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn enumerate_almost_locked_sets() {
+        let grid = Sudoku::create_board(
+            "123456700\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000"
+                .to_string(),
+        )
+        .unwrap()
+        .candidate_grid();
+        let sets = grid.almost_locked_sets();
+        // The two open cells of the first row have candidates 8 and 9, so each of them is
+        // an almost locked set on its own; together they are locked.
+        let row_sets: Vec<_> = sets
+            .iter()
+            .filter(|set| set.cells.iter().all(|cell| *cell < 9))
+            .collect();
+        assert_eq!(row_sets.len(), 2);
+        assert!(row_sets.iter().all(|set| set.candidates == 0b1_1000_0000));
+        assert_eq!(row_sets[0].to_string(), "r1c8 (89)");
+    }
+
+    #[test]
+    fn find_als_xz() {
+        // A = r1c1 (12) and B = r1c5,r1c6 (123) share the restricted common 1, so the 2
+        // is in one of them.
+        let grid = grid_with(&[(0, 0, &[1, 2]), (0, 4, &[1, 3]), (0, 5, &[2, 3])]);
+        let step = grid.find_als_xz().unwrap();
+        assert_eq!(step.technique, Technique::AlsXz);
+        match &step.detail {
+            StepDetail::AlmostLockedSets {
+                sets,
+                restricted_commons,
+                stem,
+            } => {
+                assert_eq!(sets.len(), 2);
+                assert_eq!(stem, &None);
+                assert!(restricted_commons.len() == 1);
+            }
+            other => panic!("expected almost locked sets, got {other:?}"),
+        }
+        let mut applied = grid.clone();
+        applied.apply(&step);
+        assert!(step
+            .eliminations
+            .iter()
+            .all(|e| !applied.has_candidate(e.cell, e.digit)));
+    }
+
+    #[test]
+    fn find_als_xy_wing() {
+        // C = r5c5 (12), A = r1c5 (13) shares 1 with it and B = r5c1 (23) shares 2, so
+        // r1c1 can not be 3. This is an XY-Wing made of almost locked sets.
+        let grid = grid_with(&[(4, 4, &[1, 2]), (0, 4, &[1, 3]), (4, 0, &[2, 3])]);
+        let step = grid.find_als_xy_wing().unwrap();
+        assert_eq!(step.technique, Technique::AlsXyWing);
+        assert_eq!(step.eliminations, vec![Candidate::new(cell_index(0, 0), 3)]);
+    }
+
+    #[test]
+    fn find_death_blossom() {
+        // The stem r5c5 (12) has the petal r1c5 (13) for 1 and r5c1 (23) for 2, so one
+        // of them is 3.
+        let grid = grid_with(&[(4, 4, &[1, 2]), (0, 4, &[1, 3]), (4, 0, &[2, 3])]);
+        let step = grid.find_death_blossom().unwrap();
+        assert_eq!(step.technique, Technique::DeathBlossom);
+        assert_eq!(step.eliminations, vec![Candidate::new(cell_index(0, 0), 3)]);
+        assert_eq!(
+            step.to_string(),
+            "Death Blossom with stem r5c5 and petals r1c5 (13); r5c1 (23): r1c1<>3"
+        );
+    }
+
+    #[test]
+    fn find_almost_locked_sets_in_sudoku() {
+        let sudoku = Sudoku::create_board(
+            "010900250\
+             009000073\
+             020060080\
+             047600892\
+             000008600\
+             060000100\
+             002503000\
+             000400009\
+             000070000"
+                .to_string(),
+        )
+        .unwrap();
+        let solution = sudoku.solve_logically();
+        let als_steps: Vec<_> = solution
+            .steps
+            .iter()
+            .filter(|step| [Technique::AlsXz, Technique::AlsXyWing].contains(&step.technique))
+            .collect();
+        assert!(!als_steps.is_empty());
+        assert_steps_agree_with_backtracking(&sudoku, als_steps);
+    }
+}
@@ -0,0 +1,385 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::sudoku::digits;
+use crate::sudoku::logic::{
+    cell_name, sees, Candidate, CandidateGrid, House, Step, StepDetail, Technique,
+};
+
+/// The number of candidates a chain may have when [`CandidateGrid::find_step`] looks for
+/// one. Use [`CandidateGrid::find_chain`] to search further.
+pub const DEFAULT_CHAIN_LENGTH: usize = 12;
+
+fn node_index(candidate: Candidate) -> usize {
+    candidate.cell * 9 + candidate.digit as usize - 1
+}
+
+fn node_candidate(index: usize) -> Candidate {
+    Candidate::new(index / 9, (index % 9) as u8 + 1)
+}
+
+/// Whether at most one of two different candidates can be true: they are in the same
+/// cell, or they are the same digit in cells that see each other.
+pub fn is_weak_link(first: Candidate, second: Candidate) -> bool {
+    first != second
+        && (first.cell == second.cell
+            || (first.digit == second.digit && sees(first.cell, second.cell)))
+}
+
+/// The links between the candidates of a grid. A strong link joins two candidates of
+/// which at least one is true: the two candidates of a cell with two candidates, or the
+/// only two places for a digit in a row, column or block. A weak link joins two
+/// candidates of which at most one is true. Every strong link is also a weak link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InferenceGraph {
+    strong: Vec<Vec<Candidate>>,
+    weak: Vec<Vec<Candidate>>,
+}
+
+impl InferenceGraph {
+    pub fn new(grid: &CandidateGrid) -> Self {
+        let mut strong = vec![Vec::new(); 729];
+        let mut weak = vec![Vec::new(); 729];
+        for cell in 0..81 {
+            let mask = grid.candidates(cell);
+            for digit in digits(mask) {
+                let candidate = Candidate::new(cell, digit);
+                if mask.count_ones() == 2 {
+                    let other = digits(mask).find(|other| *other != digit).unwrap();
+                    strong[node_index(candidate)].push(Candidate::new(cell, other));
+                }
+                weak[node_index(candidate)] = (0..81)
+                    .flat_map(|other| digits(grid.candidates(other)).map(move |d| (other, d)))
+                    .map(|(other, d)| Candidate::new(other, d))
+                    .filter(|other| is_weak_link(candidate, *other))
+                    .collect();
+            }
+        }
+        for house in House::all() {
+            for digit in 1..=9 {
+                let cells = grid.cells_with_candidate(house, digit);
+                if cells.len() != 2 {
+                    continue;
+                }
+                let (first, second) = (
+                    Candidate::new(cells[0], digit),
+                    Candidate::new(cells[1], digit),
+                );
+                for (from, to) in [(first, second), (second, first)] {
+                    if !strong[node_index(from)].contains(&to) {
+                        strong[node_index(from)].push(to);
+                    }
+                }
+            }
+        }
+        InferenceGraph { strong, weak }
+    }
+
+    /// The candidates with a strong link to the given candidate.
+    pub fn strong_links(&self, candidate: Candidate) -> &[Candidate] {
+        &self.strong[node_index(candidate)]
+    }
+
+    /// The candidates with a weak link to the given candidate.
+    pub fn weak_links(&self, candidate: Candidate) -> &[Candidate] {
+        &self.weak[node_index(candidate)]
+    }
+}
+
+/// An alternating inference chain: the links between consecutive candidates are strong
+/// and weak in turn, starting and ending with a strong link. Either the first or the last
+/// candidate is true, so every candidate with a weak link to both ends is false.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chain {
+    pub nodes: Vec<Candidate>,
+}
+
+impl Chain {
+    pub fn first(&self) -> Candidate {
+        self.nodes[0]
+    }
+
+    pub fn last(&self) -> Candidate {
+        self.nodes[self.nodes.len() - 1]
+    }
+
+    /// The cells the chain passes through, in order and without repeats.
+    pub fn cells(&self) -> Vec<usize> {
+        let mut cells: Vec<usize> = Vec::new();
+        for node in self.nodes.iter() {
+            if !cells.contains(&node.cell) {
+                cells.push(node.cell);
+            }
+        }
+        cells
+    }
+}
+
+/// Eureka notation: `=` for a strong link and `-` for a weak link, with consecutive
+/// candidates in the same cell written together, as in `(1=2)r1c1-(2)r1c5=(2)r3c4`.
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let link = |index: usize| if index.is_multiple_of(2) { '=' } else { '-' };
+        let mut start = 0;
+        while start < self.nodes.len() {
+            let cell = self.nodes[start].cell;
+            let mut end = start;
+            while end + 1 < self.nodes.len() && self.nodes[end + 1].cell == cell {
+                end += 1;
+            }
+            if start > 0 {
+                write!(f, "{}", link(start - 1))?;
+            }
+            write!(f, "({}", self.nodes[start].digit)?;
+            for index in start + 1..=end {
+                write!(f, "{}{}", link(index - 1), self.nodes[index].digit)?;
+            }
+            write!(f, "){}", cell_name(cell))?;
+            start = end + 1;
+        }
+        Ok(())
+    }
+}
+
+/// The candidates that a chain removes: every candidate outside the chain with a weak link
+/// to both of its ends.
+pub(crate) fn chain_eliminations(graph: &InferenceGraph, chain: &Chain) -> Vec<Candidate> {
+    let mut eliminations: Vec<Candidate> = graph
+        .weak_links(chain.first())
+        .iter()
+        .copied()
+        .filter(|candidate| !chain.nodes.contains(candidate))
+        .filter(|candidate| is_weak_link(*candidate, chain.last()))
+        .collect();
+    eliminations.sort();
+    eliminations
+}
+
+/// The links a kind of chain may use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChainKind {
+    /// Only one digit, with strong links between the two places for it in a house.
+    X,
+    /// Strong links inside cells with two candidates, weak links between cells on a digit.
+    XY,
+    /// Any link.
+    Alternating,
+}
+
+impl ChainKind {
+    fn allows_strong(&self, from: Candidate, to: Candidate) -> bool {
+        match self {
+            ChainKind::X => from.digit == to.digit,
+            ChainKind::XY => from.cell == to.cell,
+            ChainKind::Alternating => true,
+        }
+    }
+
+    fn allows_weak(&self, from: Candidate, to: Candidate) -> bool {
+        match self {
+            ChainKind::X | ChainKind::XY => from.digit == to.digit,
+            ChainKind::Alternating => true,
+        }
+    }
+}
+
+impl CandidateGrid {
+    pub fn inference_graph(&self) -> InferenceGraph {
+        InferenceGraph::new(self)
+    }
+
+    /// The shortest chain of the technique with at most `max_length` candidates that
+    /// removes a candidate, or `None` when there is none or the technique is not a
+    /// chain.
+    pub fn find_chain(&self, technique: Technique, max_length: usize) -> Option<Step> {
+        let kind = match technique {
+            Technique::XChain => ChainKind::X,
+            Technique::XYChain => ChainKind::XY,
+            Technique::AlternatingInferenceChain => ChainKind::Alternating,
+            _ => return None,
+        };
+        let graph = self.inference_graph();
+        let mut best: Option<(Chain, Vec<Candidate>)> = None;
+        for start in 0..729 {
+            if !self.has_candidate(start / 9, (start % 9) as u8 + 1) {
+                continue;
+            }
+            let limit = match &best {
+                Some((chain, _)) => chain.nodes.len() - 1,
+                None => max_length,
+            };
+            if let Some(found) = self.search_chain(&graph, kind, node_candidate(start), limit) {
+                best = Some(found);
+            }
+        }
+        best.map(|(chain, eliminations)| Step {
+            technique,
+            cells: chain.cells(),
+            houses: Vec::new(),
+            placements: Vec::new(),
+            eliminations,
+            detail: StepDetail::Chain(chain),
+        })
+    }
+
+    /// Breadth first search for the shortest chain from a candidate that removes
+    /// something. A state is a candidate together with the kind of link that has to
+    /// follow it, so a candidate can be reached once after a strong and once after a weak
+    /// link.
+    fn search_chain(
+        &self,
+        graph: &InferenceGraph,
+        kind: ChainKind,
+        start: Candidate,
+        max_length: usize,
+    ) -> Option<(Chain, Vec<Candidate>)> {
+        // For each state the previous state and the number of candidates up to it; index
+        // 2 * node for states that need a strong link next, 2 * node + 1 for a weak one.
+        let mut previous: Vec<Option<(usize, usize)>> = vec![None; 729 * 2];
+        let start_state = node_index(start) * 2;
+        previous[start_state] = Some((start_state, 1));
+        let mut queue = VecDeque::from([start_state]);
+        let path = |previous: &[Option<(usize, usize)>], mut state: usize| {
+            let mut nodes = vec![node_candidate(state / 2)];
+            while state != start_state {
+                state = previous[state].unwrap().0;
+                nodes.push(node_candidate(state / 2));
+            }
+            nodes.reverse();
+            nodes
+        };
+        while let Some(state) = queue.pop_front() {
+            let (node, length) = (node_candidate(state / 2), previous[state].unwrap().1);
+            if length >= max_length {
+                continue;
+            }
+            let strong_next = state.is_multiple_of(2);
+            let links = if strong_next {
+                graph.strong_links(node)
+            } else {
+                graph.weak_links(node)
+            };
+            let nodes = path(&previous, state);
+            for next in links.iter().copied() {
+                let allowed = if strong_next {
+                    kind.allows_strong(node, next)
+                } else {
+                    kind.allows_weak(node, next)
+                };
+                let next_state = node_index(next) * 2 + usize::from(strong_next);
+                if !allowed || previous[next_state].is_some() || nodes.contains(&next) {
+                    continue;
+                }
+                previous[next_state] = Some((state, length + 1));
+                if strong_next && length + 1 >= 4 {
+                    let chain = Chain {
+                        nodes: path(&previous, next_state),
+                    };
+                    let eliminations = chain_eliminations(graph, &chain);
+                    if !eliminations.is_empty() {
+                        return Some((chain, eliminations));
+                    }
+                }
+                queue.push_back(next_state);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::logic::test_support::{assert_steps_agree_with_backtracking, grid_with};
+    use crate::sudoku::logic::{cell_index, Candidate, Chain, Step, StepDetail, Technique};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn link_candidates() {
+        let grid = grid_with(&[(0, 0, &[1, 2])]);
+        let graph = grid.inference_graph();
+        let first = Candidate::new(0, 1);
+        assert_eq!(graph.strong_links(first), &[Candidate::new(0, 2)]);
+        // The other 1s in the row, column and block, and the 2 in the same cell.
+        assert_eq!(graph.weak_links(first).len(), 21);
+        assert!(graph.strong_links(Candidate::new(1, 1)).is_empty());
+    }
+
+    #[test]
+    fn write_eureka_notation() {
+        let chain = Chain {
+            nodes: vec![
+                Candidate::new(cell_index(0, 0), 1),
+                Candidate::new(cell_index(0, 0), 2),
+                Candidate::new(cell_index(0, 4), 2),
+                Candidate::new(cell_index(2, 3), 2),
+            ],
+        };
+        assert_eq!(chain.to_string(), "(1=2)r1c1-(2)r1c5=(2)r3c4");
+    }
+
+    #[test]
+    fn find_x_chain() {
+        let mut grid = grid_with(&[]);
+        for (row, keep) in [(1, [2, 6]), (4, [2, 7])] {
+            for column in (0..9).filter(|column| !keep.contains(column)) {
+                grid.eliminate(cell_index(row, column), 5);
+            }
+        }
+        let step = grid.find_chain(Technique::XChain, 12).unwrap();
+        let cells: Vec<usize> = step.eliminations.iter().map(|e| e.cell).collect();
+        assert_eq!(
+            cells,
+            vec![
+                cell_index(0, 7),
+                cell_index(2, 7),
+                cell_index(3, 6),
+                cell_index(5, 6)
+            ]
+        );
+        match &step.detail {
+            StepDetail::Chain(chain) => assert_eq!(chain.nodes.len(), 4),
+            other => panic!("expected a chain, got {other:?}"),
+        }
+        assert!(grid.find_chain(Technique::XChain, 3).is_none());
+    }
+
+    #[test]
+    fn find_xy_chain() {
+        let grid = grid_with(&[
+            (0, 0, &[1, 2]),
+            (0, 5, &[2, 3]),
+            (4, 5, &[3, 4]),
+            (4, 1, &[1, 4]),
+        ]);
+        let step = grid.find_chain(Technique::XYChain, 12).unwrap();
+        assert!(step.eliminations.iter().all(|e| e.digit == 1));
+        assert_eq!(step.eliminations.len(), 6);
+        assert_eq!(step.cells.len(), 4);
+        assert!(grid.find_chain(Technique::XYChain, 6).is_none());
+    }
+
+    #[test]
+    fn find_alternating_inference_chain_in_sudoku() {
+        let sudoku = Sudoku::create_board(
+            "000000405\
+             040090008\
+             060700300\
+             000960007\
+             008403100\
+             000001000\
+             005000002\
+             081600000\
+             902000600"
+                .to_string(),
+        )
+        .unwrap();
+        let solution = sudoku.candidate_grid().solve();
+        let chains: Vec<&Step> = solution
+            .steps
+            .iter()
+            .filter(|step| step.technique == Technique::AlternatingInferenceChain)
+            .collect();
+        assert!(!chains.is_empty());
+        assert_steps_agree_with_backtracking(&sudoku, chains);
+    }
+}
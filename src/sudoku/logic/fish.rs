@@ -0,0 +1,294 @@
+use crate::sudoku::digits;
+use crate::sudoku::logic::{
+    block_of, column_of, combinations, row_of, Candidate, CandidateGrid, House, Step, StepDetail,
+    Technique,
+};
+
+/// Whether a fish uses rows as base sets and columns as cover sets, or the other way around.
+#[derive(Clone, Copy)]
+enum Orientation {
+    Rows,
+    Columns,
+}
+
+impl Orientation {
+    fn base(&self, index: usize) -> House {
+        match self {
+            Orientation::Rows => House::Row(index),
+            Orientation::Columns => House::Column(index),
+        }
+    }
+
+    fn cover(&self, index: usize) -> House {
+        match self {
+            Orientation::Rows => House::Column(index),
+            Orientation::Columns => House::Row(index),
+        }
+    }
+
+    /// The index of the base set a cell lies in.
+    fn base_index(&self, cell: usize) -> usize {
+        match self {
+            Orientation::Rows => row_of(cell),
+            Orientation::Columns => column_of(cell),
+        }
+    }
+
+    /// The index of the cover set a cell lies in.
+    fn cover_index(&self, cell: usize) -> usize {
+        match self {
+            Orientation::Rows => column_of(cell),
+            Orientation::Columns => row_of(cell),
+        }
+    }
+}
+
+fn fish_technique(size: usize, finned: bool, sashimi: bool) -> Technique {
+    match (size, finned, sashimi) {
+        (2, false, _) => Technique::XWing,
+        (3, false, _) => Technique::Swordfish,
+        (4, false, _) => Technique::Jellyfish,
+        (2, true, false) => Technique::FinnedXWing,
+        (3, true, false) => Technique::FinnedSwordfish,
+        (4, true, false) => Technique::FinnedJellyfish,
+        (2, true, true) => Technique::SashimiXWing,
+        (3, true, true) => Technique::SashimiSwordfish,
+        (4, true, true) => Technique::SashimiJellyfish,
+        _ => panic!("a fish has 2, 3 or 4 base sets, not {size}"),
+    }
+}
+
+impl CandidateGrid {
+    /// The candidates for a digit in a line as a bit mask of their positions across
+    /// the line.
+    fn fish_positions(&self, orientation: Orientation, line: usize, digit: u8) -> u16 {
+        self.cells_with_candidate(orientation.base(line), digit)
+            .iter()
+            .fold(0, |mask, cell| mask | 1 << orientation.cover_index(*cell))
+    }
+
+    /// When the candidates for a digit in `size` rows all lie in the same `size` columns,
+    /// each of those columns has its digit in one of the rows, so the digit can be removed
+    /// from the rest of the columns. The same holds with rows and columns swapped.
+    pub(crate) fn find_fish(&self, size: usize) -> Option<Step> {
+        for digit in 1..=9 {
+            for orientation in [Orientation::Rows, Orientation::Columns] {
+                let lines: Vec<usize> = (0..9)
+                    .filter(|line| {
+                        let count = self.fish_positions(orientation, *line, digit).count_ones();
+                        count >= 2 && count as usize <= size
+                    })
+                    .collect();
+                for base in combinations(&lines, size) {
+                    let cover = base.iter().fold(0, |mask, line| {
+                        mask | self.fish_positions(orientation, *line, digit)
+                    });
+                    if cover.count_ones() as usize != size {
+                        continue;
+                    }
+                    let technique = fish_technique(size, false, false);
+                    let step = self.fish_step(technique, orientation, digit, &base, cover, &[]);
+                    if !step.eliminations.is_empty() {
+                        return Some(step);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// A fish that only works once the fins, extra candidates in the base sets that all lie
+    /// in one block, are left out. Either a fin holds the digit or the fish does, so the
+    /// digit can only be removed from cover set cells in the block of the fins. With
+    /// `sashimi` set, only fish where a base set has a single candidate besides the fins
+    /// are reported, otherwise only fish where every base set has at least two.
+    pub(crate) fn find_finned_fish(&self, size: usize, sashimi: bool) -> Option<Step> {
+        for digit in 1..=9 {
+            for orientation in [Orientation::Rows, Orientation::Columns] {
+                let lines: Vec<usize> = (0..9)
+                    .filter(|line| self.fish_positions(orientation, *line, digit) != 0)
+                    .collect();
+                for base in combinations(&lines, size) {
+                    let positions: Vec<u16> = base
+                        .iter()
+                        .map(|line| self.fish_positions(orientation, *line, digit))
+                        .collect();
+                    let union = positions.iter().fold(0, |mask, p| mask | p);
+                    // Fins in one block span at most three cover sets.
+                    let count = union.count_ones() as usize;
+                    if count <= size || count > size + 3 {
+                        continue;
+                    }
+                    let union_indices: Vec<usize> = digits(union).map(|d| d as usize - 1).collect();
+                    for cover_indices in combinations(&union_indices, size) {
+                        let cover = cover_indices
+                            .iter()
+                            .fold(0u16, |mask, index| mask | 1 << index);
+                        let bodies: Vec<u32> =
+                            positions.iter().map(|p| (p & cover).count_ones()).collect();
+                        if bodies.contains(&0) || bodies.contains(&1) != sashimi {
+                            continue;
+                        }
+                        let fins: Vec<usize> = base
+                            .iter()
+                            .flat_map(|line| {
+                                self.cells_with_candidate(orientation.base(*line), digit)
+                            })
+                            .filter(|cell| cover & 1 << orientation.cover_index(*cell) == 0)
+                            .collect();
+                        let fin_block = block_of(fins[0]);
+                        if fins.iter().any(|fin| block_of(*fin) != fin_block) {
+                            continue;
+                        }
+                        let technique = fish_technique(size, true, sashimi);
+                        let step =
+                            self.fish_step(technique, orientation, digit, &base, cover, &fins);
+                        if !step.eliminations.is_empty() {
+                            return Some(step);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Build the step of a fish with the given base sets and cover sets. Without fins the
+    /// digit is removed from every other cell of the cover sets, with fins only from those
+    /// in the block of the fins.
+    fn fish_step(
+        &self,
+        technique: Technique,
+        orientation: Orientation,
+        digit: u8,
+        base: &[usize],
+        cover: u16,
+        fins: &[usize],
+    ) -> Step {
+        let cover_indices: Vec<usize> = digits(cover).map(|d| d as usize - 1).collect();
+        let base_sets: Vec<House> = base.iter().map(|line| orientation.base(*line)).collect();
+        let cover_sets: Vec<House> = cover_indices
+            .iter()
+            .map(|index| orientation.cover(*index))
+            .collect();
+        let mut eliminations: Vec<Candidate> = cover_sets
+            .iter()
+            .flat_map(|house| self.cells_with_candidate(*house, digit))
+            .filter(|cell| !base.contains(&orientation.base_index(*cell)))
+            .filter(|cell| fins.is_empty() || block_of(*cell) == block_of(fins[0]))
+            .map(|cell| Candidate::new(cell, digit))
+            .collect();
+        eliminations.sort();
+        let cells: Vec<usize> = base_sets
+            .iter()
+            .flat_map(|house| self.cells_with_candidate(*house, digit))
+            .collect();
+        Step {
+            technique,
+            cells,
+            houses: base_sets.iter().chain(cover_sets.iter()).copied().collect(),
+            placements: Vec::new(),
+            eliminations,
+            detail: StepDetail::Fish {
+                digit,
+                base_sets,
+                cover_sets,
+                fins: fins.to_vec(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::logic::test_support::{
+        assert_steps_agree_with_backtracking, grid_with_places,
+    };
+    use crate::sudoku::logic::{cell_index, House, StepDetail, Technique};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn find_x_wing() {
+        let grid = grid_with_places(5, &[(1, &[2, 6]), (4, &[2, 6])]);
+        let step = grid.find_fish(2).unwrap();
+        assert_eq!(step.technique, Technique::XWing);
+        assert_eq!(
+            step.detail,
+            StepDetail::Fish {
+                digit: 5,
+                base_sets: vec![House::Row(1), House::Row(4)],
+                cover_sets: vec![House::Column(2), House::Column(6)],
+                fins: Vec::new(),
+            }
+        );
+        assert_eq!(step.eliminations.len(), 14);
+        assert!(step
+            .eliminations
+            .iter()
+            .all(|e| e.digit == 5 && [2, 6].contains(&(e.cell % 9))));
+        assert!(grid.find_fish(3).is_none());
+        assert_eq!(
+            step.to_string().split(':').next().unwrap(),
+            "X-Wing on 5 in r2,r5 over c3,c7"
+        );
+    }
+
+    #[test]
+    fn find_finned_x_wing() {
+        let grid = grid_with_places(5, &[(1, &[2, 6, 7]), (4, &[2, 6])]);
+        assert!(grid.find_fish(2).is_none());
+        let step = grid.find_finned_fish(2, false).unwrap();
+        assert_eq!(step.technique, Technique::FinnedXWing);
+        match &step.detail {
+            StepDetail::Fish {
+                cover_sets, fins, ..
+            } => {
+                assert_eq!(cover_sets, &vec![House::Column(2), House::Column(6)]);
+                assert_eq!(fins, &vec![cell_index(1, 7)]);
+            }
+            other => panic!("expected a fish, got {other:?}"),
+        }
+        let cells: Vec<usize> = step.eliminations.iter().map(|e| e.cell).collect();
+        assert_eq!(cells, vec![cell_index(0, 6), cell_index(2, 6)]);
+        assert!(grid.find_finned_fish(2, true).is_none());
+    }
+
+    #[test]
+    fn find_sashimi_x_wing() {
+        let grid = grid_with_places(5, &[(1, &[6, 7]), (4, &[2, 6])]);
+        assert!(grid.find_finned_fish(2, false).is_none());
+        let step = grid.find_finned_fish(2, true).unwrap();
+        assert_eq!(step.technique, Technique::SashimiXWing);
+        let cells: Vec<usize> = step.eliminations.iter().map(|e| e.cell).collect();
+        assert_eq!(cells, vec![cell_index(0, 6), cell_index(2, 6)]);
+    }
+
+    #[test]
+    fn find_swordfish_in_sudoku() {
+        let sudoku = Sudoku::create_board(
+            "504800070\
+             300050006\
+             080003000\
+             000010500\
+             000000000\
+             076020109\
+             205001060\
+             000062003\
+             000300091"
+                .to_string(),
+        )
+        .unwrap();
+        let solution = sudoku.solve_logically();
+        let step = solution
+            .steps
+            .iter()
+            .find(|step| step.technique == Technique::Swordfish)
+            .unwrap();
+        assert_eq!(
+            step.to_string(),
+            "Swordfish on 4 in c2,c4,c8 over r4,r5,r8: \
+             r4c1<>4, r4c6<>4, r5c1<>4, r5c6<>4, r8c1<>4"
+        );
+        assert_steps_agree_with_backtracking(&sudoku, [step]);
+    }
+}
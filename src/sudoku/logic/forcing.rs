@@ -0,0 +1,283 @@
+use std::fmt;
+
+use crate::sudoku::digits;
+use crate::sudoku::logic::{
+    cell_name, Candidate, CandidateGrid, House, Step, StepDetail, Technique,
+};
+
+/// The number of singles a forcing chain follows from each assumption when
+/// [`CandidateGrid::find_step`] looks for one. Use [`CandidateGrid::find_forcing_chain`]
+/// to look further.
+pub const DEFAULT_FORCING_DEPTH: usize = 20;
+
+/// What follows from assuming a candidate is true: the singles that fill in after it, and
+/// whether they end in a contradiction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Derivation {
+    pub assumption: Candidate,
+    pub steps: Vec<Step>,
+    pub contradiction: bool,
+}
+
+/// The assumption and every placement after it, as in `r1c1=5 -> r2c3=4 -> contradiction`.
+impl fmt::Display for Derivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}={}",
+            cell_name(self.assumption.cell),
+            self.assumption.digit
+        )?;
+        for placement in self.steps.iter().flat_map(|step| step.placements.iter()) {
+            write!(f, " -> {}={}", cell_name(placement.cell), placement.digit)?;
+        }
+        if self.contradiction {
+            write!(f, " -> contradiction")?;
+        }
+        Ok(())
+    }
+}
+
+/// A value or candidate that is still open in the grid: the digit is placed in the cell
+/// or is one of its candidates.
+fn allows(grid: &CandidateGrid, cell: usize, digit: u8) -> bool {
+    grid.value(cell) == digit || grid.has_candidate(cell, digit)
+}
+
+impl CandidateGrid {
+    /// Place the assumed candidate and follow the singles after it, at most `depth` of
+    /// them. With `digit_only` set only singles of the assumed digit are followed, as in
+    /// Nishio. Returns the derivation and the grid it ends in.
+    pub(crate) fn derive(
+        &self,
+        assumption: Candidate,
+        digit_only: bool,
+        depth: usize,
+    ) -> (Derivation, CandidateGrid) {
+        let mut grid = self.clone();
+        grid.place(assumption.cell, assumption.digit);
+        let mut steps = Vec::new();
+        while !grid.has_contradiction() && steps.len() < depth {
+            let single = if digit_only {
+                House::all().find_map(|house| grid.hidden_single_in(house, assumption.digit))
+            } else {
+                grid.find_hidden_single()
+                    .or_else(|| grid.find_naked_single())
+            };
+            match single {
+                Some(step) => {
+                    grid.apply(&step);
+                    steps.push(step);
+                }
+                None => break,
+            }
+        }
+        let derivation = Derivation {
+            assumption,
+            steps,
+            contradiction: grid.has_contradiction(),
+        };
+        (derivation, grid)
+    }
+
+    /// The step of a forcing chain of the technique, following at most `depth` singles
+    /// from each assumption, or `None` when there is none or the technique is not a
+    /// forcing chain.
+    pub fn find_forcing_chain(&self, technique: Technique, depth: usize) -> Option<Step> {
+        match technique {
+            Technique::Nishio => self.find_contradiction(technique, true, depth),
+            Technique::ContradictionChain => self.find_contradiction(technique, false, depth),
+            Technique::CellForcingChain => (0..81)
+                .filter(|cell| self.candidates(*cell).count_ones() >= 2)
+                .find_map(|cell| {
+                    let assumptions: Vec<Candidate> = digits(self.candidates(cell))
+                        .map(|digit| Candidate::new(cell, digit))
+                        .collect();
+                    self.common_consequences(technique, &assumptions, Vec::new(), depth)
+                }),
+            Technique::UnitForcingChain => House::all()
+                .flat_map(|house| (1..=9).map(move |digit| (house, digit)))
+                .filter(|(house, digit)| self.cells_with_candidate(*house, *digit).len() >= 2)
+                .find_map(|(house, digit)| {
+                    let assumptions: Vec<Candidate> = self
+                        .cells_with_candidate(house, digit)
+                        .into_iter()
+                        .map(|cell| Candidate::new(cell, digit))
+                        .collect();
+                    self.common_consequences(technique, &assumptions, vec![house], depth)
+                }),
+            _ => None,
+        }
+    }
+
+    /// Assume each candidate in turn; the first one whose singles end in a contradiction
+    /// is false.
+    fn find_contradiction(
+        &self,
+        technique: Technique,
+        digit_only: bool,
+        depth: usize,
+    ) -> Option<Step> {
+        for cell in 0..81 {
+            for digit in digits(self.candidates(cell)) {
+                let assumption = Candidate::new(cell, digit);
+                let (derivation, _) = self.derive(assumption, digit_only, depth);
+                if derivation.contradiction {
+                    return Some(Step {
+                        technique,
+                        cells: vec![cell],
+                        houses: Vec::new(),
+                        placements: Vec::new(),
+                        eliminations: vec![assumption],
+                        detail: StepDetail::Forcing {
+                            derivations: vec![derivation],
+                        },
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// One of the assumptions is true, so whatever follows from every one of them is
+    /// true as well: a value placed in all branches, or a candidate removed in all of
+    /// them. Assumptions that lead to a contradiction are left to the contradiction
+    /// chains.
+    fn common_consequences(
+        &self,
+        technique: Technique,
+        assumptions: &[Candidate],
+        houses: Vec<House>,
+        depth: usize,
+    ) -> Option<Step> {
+        let mut derivations = Vec::new();
+        let mut grids = Vec::new();
+        for assumption in assumptions {
+            let (derivation, grid) = self.derive(*assumption, false, depth);
+            if derivation.contradiction {
+                return None;
+            }
+            derivations.push(derivation);
+            grids.push(grid);
+        }
+        let mut placements = Vec::new();
+        let mut eliminations = Vec::new();
+        for cell in (0..81).filter(|cell| self.value(*cell) == 0) {
+            for digit in digits(self.candidates(cell)) {
+                if grids.iter().all(|grid| grid.value(cell) == digit) {
+                    placements.push(Candidate::new(cell, digit));
+                } else if grids.iter().all(|grid| !allows(grid, cell, digit)) {
+                    eliminations.push(Candidate::new(cell, digit));
+                }
+            }
+        }
+        if placements.is_empty() && eliminations.is_empty() {
+            return None;
+        }
+        Some(Step {
+            technique,
+            cells: assumptions
+                .iter()
+                .map(|assumption| assumption.cell)
+                .collect(),
+            houses,
+            placements,
+            eliminations,
+            detail: StepDetail::Forcing { derivations },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::logic::test_support::assert_steps_agree_with_backtracking;
+    use crate::sudoku::logic::{Candidate, StepDetail, Technique};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn derive_singles_from_assumption() {
+        // With a 1 in the top left corner the last open cell of the first row is a 9.
+        let sudoku = Sudoku::create_board(
+            "002345678\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000"
+                .to_string(),
+        )
+        .unwrap();
+        let grid = sudoku.candidate_grid();
+        let (derivation, after) = grid.derive(Candidate::new(0, 1), false, 10);
+        assert!(!derivation.contradiction);
+        assert_eq!(after.value(1), 9);
+        assert!(derivation.to_string().starts_with("r1c1=1 -> "));
+        let (limited, _) = grid.derive(Candidate::new(0, 1), false, 0);
+        assert!(limited.steps.is_empty());
+    }
+
+    #[test]
+    fn find_contradiction_chain() {
+        // A 1 in the top left corner leaves no place for the 1 of the second row, which
+        // has its other open cells in the same block.
+        let sudoku = Sudoku::create_board(
+            "000000000\
+             000234567\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000"
+                .to_string(),
+        )
+        .unwrap();
+        let grid = sudoku.candidate_grid();
+        let step = grid.find_forcing_chain(Technique::Nishio, 5).unwrap();
+        assert_eq!(step.eliminations, vec![Candidate::new(0, 1)]);
+        match &step.detail {
+            StepDetail::Forcing { derivations } => {
+                assert_eq!(derivations.len(), 1);
+                assert!(derivations[0].contradiction);
+                assert_eq!(derivations[0].to_string(), "r1c1=1 -> contradiction");
+            }
+            other => panic!("expected a forcing chain, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn forcing_chains_agree_with_solution() {
+        let sudoku = Sudoku::create_board(
+            "000000405\
+             040090008\
+             060700300\
+             000960007\
+             008403100\
+             000001000\
+             005000002\
+             081600000\
+             902000600"
+                .to_string(),
+        )
+        .unwrap();
+        let grid = sudoku.candidate_grid();
+        for technique in [
+            Technique::Nishio,
+            Technique::ContradictionChain,
+            Technique::CellForcingChain,
+            Technique::UnitForcingChain,
+        ] {
+            let step = grid.find_forcing_chain(technique, 20).unwrap();
+            assert_steps_agree_with_backtracking(&sudoku, [&step]);
+        }
+        assert_eq!(grid.find_forcing_chain(Technique::XWing, 20), None);
+        let step = grid
+            .find_forcing_chain(Technique::CellForcingChain, 20)
+            .unwrap();
+        assert!(step.cells.iter().all(|cell| *cell == step.cells[0]));
+    }
+}
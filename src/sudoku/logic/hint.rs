@@ -0,0 +1,249 @@
+use std::fmt;
+
+use crate::sudoku::logic::{block_of, cell_name, House, Step, Technique};
+use crate::sudoku::solver::Uniqueness;
+use crate::sudoku::Sudoku;
+
+/// How much of the next step a hint gives away, from least to most.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HintLevel {
+    /// The row, column or block to look at.
+    Region,
+    /// The technique that makes progress.
+    Technique,
+    /// The technique and the cell it places a value in or removes a candidate from.
+    Cell,
+    /// The whole step with an explanation.
+    Step,
+}
+
+/// The answer to [`Sudoku::hint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hint {
+    /// The board can no longer be completed, so one of its values is wrong.
+    Mistake,
+    /// Every cell is filled.
+    Solved,
+    /// None of the techniques makes progress on the board.
+    NoStep,
+    Region(House),
+    Technique(Technique),
+    Cell {
+        technique: Technique,
+        cell: usize,
+    },
+    Step {
+        step: Step,
+        explanation: String,
+    },
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hint::Mistake => write!(f, "The board contains a mistake"),
+            Hint::Solved => write!(f, "The board is solved"),
+            Hint::NoStep => write!(f, "No known technique makes progress"),
+            Hint::Region(house) => write!(f, "Look at {house}"),
+            Hint::Technique(technique) => write!(f, "Look for a {technique}"),
+            Hint::Cell { technique, cell } => {
+                write!(
+                    f,
+                    "Look for a {technique} that affects {}",
+                    cell_name(*cell)
+                )
+            }
+            Hint::Step { explanation, .. } => write!(f, "{explanation}"),
+        }
+    }
+}
+
+impl Step {
+    /// The cell the step places a value in, or else the first cell it removes a
+    /// candidate from.
+    pub fn target_cell(&self) -> Option<usize> {
+        self.placements
+            .first()
+            .or(self.eliminations.first())
+            .map(|candidate| candidate.cell)
+    }
+
+    /// The house the step works in, or else the block of its target cell.
+    pub fn region(&self) -> Option<House> {
+        self.houses
+            .first()
+            .copied()
+            .or_else(|| self.target_cell().map(|cell| House::Block(block_of(cell))))
+    }
+
+    /// The step in words, as a player would be told it.
+    pub fn explanation(&self) -> String {
+        match (self.technique, self.placements.first(), self.houses.first()) {
+            (Technique::HiddenSingle, Some(placement), Some(house)) => format!(
+                "{} is the only place left for {} in {house}, so it is {}.",
+                cell_name(placement.cell),
+                placement.digit,
+                placement.digit
+            ),
+            (Technique::NakedSingle, Some(placement), _) => format!(
+                "{} is the only candidate left in {}, so it is {}.",
+                placement.digit,
+                cell_name(placement.cell),
+                placement.digit
+            ),
+            _ => {
+                let mut effects: Vec<String> = Vec::new();
+                for placement in self.placements.iter() {
+                    effects.push(format!(
+                        "{} is {}",
+                        cell_name(placement.cell),
+                        placement.digit
+                    ));
+                }
+                for elimination in self.eliminations.iter() {
+                    effects.push(format!(
+                        "{} can not be {}",
+                        cell_name(elimination.cell),
+                        elimination.digit
+                    ));
+                }
+                format!("{self}. So {}.", effects.join(", "))
+            }
+        }
+    }
+}
+
+impl Sudoku {
+    /// A hint for the next move on a partially filled board: the easiest step the
+    /// logical solver finds, with as much detail as the level asks for. A board that no
+    /// longer has a solution gets [`Hint::Mistake`] instead.
+    pub fn hint(&self, level: HintLevel) -> Hint {
+        if self.check_sudoku_is_filled() {
+            return if self.check_sudoku_completed() {
+                Hint::Solved
+            } else {
+                Hint::Mistake
+            };
+        }
+        let mut grid = self.candidate_grid();
+        match self.uniqueness() {
+            Uniqueness::NoSolution => return Hint::Mistake,
+            Uniqueness::Unique(_) => grid.assume_unique_solution(),
+            Uniqueness::Multiple(..) => {}
+        }
+        let step = match grid.next_step() {
+            Some(step) => step,
+            None => return Hint::NoStep,
+        };
+        match level {
+            HintLevel::Region => step.region().map_or(Hint::NoStep, Hint::Region),
+            HintLevel::Technique => Hint::Technique(step.technique),
+            HintLevel::Cell => match step.target_cell() {
+                Some(cell) => Hint::Cell {
+                    technique: step.technique,
+                    cell,
+                },
+                None => Hint::NoStep,
+            },
+            HintLevel::Step => {
+                let explanation = step.explanation();
+                Hint::Step { step, explanation }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::logic::{block_of, Hint, HintLevel, House, Technique};
+    use crate::sudoku::Sudoku;
+
+    const PUZZLE: &str = "530070000\
+                          600195000\
+                          098000060\
+                          800060003\
+                          400803001\
+                          700020006\
+                          060000280\
+                          000419005\
+                          000080079";
+
+    #[test]
+    fn hint_levels() {
+        let sudoku = Sudoku::create_board(PUZZLE.to_string()).unwrap();
+        let step = sudoku.solve_logically().steps[0].clone();
+        assert_eq!(step.technique, Technique::HiddenSingle);
+        let cell = step.placements[0].cell;
+        assert_eq!(sudoku.hint(HintLevel::Region), Hint::Region(step.houses[0]));
+        assert_eq!(
+            sudoku.hint(HintLevel::Technique),
+            Hint::Technique(Technique::HiddenSingle)
+        );
+        assert_eq!(
+            sudoku.hint(HintLevel::Cell),
+            Hint::Cell {
+                technique: Technique::HiddenSingle,
+                cell
+            }
+        );
+        match sudoku.hint(HintLevel::Step) {
+            Hint::Step {
+                step: hinted,
+                explanation,
+            } => {
+                assert_eq!(hinted, step);
+                assert!(explanation.contains("is the only place left for"));
+            }
+            other => panic!("expected a step, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn hint_on_finished_or_wrong_board() {
+        let sudoku = Sudoku::create_board(PUZZLE.to_string()).unwrap();
+        let solved = sudoku.solve_logically().grid.to_sudoku();
+        assert_eq!(solved.hint(HintLevel::Step), Hint::Solved);
+
+        // A 4 in the top right corner fits the row, column and block, but leaves no
+        // solution.
+        let mut wrong = PUZZLE.to_string();
+        wrong.replace_range(8..9, "4");
+        let wrong = Sudoku::create_board(wrong).unwrap();
+        assert_eq!(wrong.hint(HintLevel::Region), Hint::Mistake);
+        assert_eq!(
+            wrong.hint(HintLevel::Region).to_string(),
+            "The board contains a mistake"
+        );
+    }
+
+    #[test]
+    fn explain_elimination() {
+        let sudoku = Sudoku::create_board(
+            "478600000\
+             000900006\
+             000804000\
+             500037000\
+             030040080\
+             004000120\
+             090000062\
+             000000700\
+             200700010"
+                .to_string(),
+        )
+        .unwrap();
+        let mut grid = sudoku.candidate_grid();
+        let step = loop {
+            let step = grid.next_step().unwrap();
+            if step.placements.is_empty() {
+                break step;
+            }
+            grid.apply(&step);
+        };
+        let explanation = step.explanation();
+        assert!(explanation.starts_with(&step.to_string()));
+        assert!(explanation.contains("can not be"));
+        assert_eq!(step.target_cell(), Some(step.eliminations[0].cell));
+        let block = House::Block(block_of(step.eliminations[0].cell));
+        assert_eq!(step.region(), step.houses.first().copied().or(Some(block)));
+    }
+}
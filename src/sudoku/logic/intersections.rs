@@ -0,0 +1,142 @@
+use crate::sudoku::logic::{
+    block_of, column_of, row_of, Candidate, CandidateGrid, House, Step, StepDetail, Technique,
+};
+
+impl CandidateGrid {
+    /// When the candidates for a digit in a block all lie in one row or column, the digit
+    /// can be removed from the rest of that row or column.
+    pub(crate) fn find_pointing_pair(&self) -> Option<Step> {
+        for block in 0..9 {
+            for digit in 1..=9 {
+                let cells = self.cells_with_candidate(House::Block(block), digit);
+                if cells.len() < 2 {
+                    continue;
+                }
+                let line = if cells.iter().all(|cell| row_of(*cell) == row_of(cells[0])) {
+                    House::Row(row_of(cells[0]))
+                } else if cells
+                    .iter()
+                    .all(|cell| column_of(*cell) == column_of(cells[0]))
+                {
+                    House::Column(column_of(cells[0]))
+                } else {
+                    continue;
+                };
+                let eliminations: Vec<Candidate> = self
+                    .cells_with_candidate(line, digit)
+                    .into_iter()
+                    .filter(|cell| block_of(*cell) != block)
+                    .map(|cell| Candidate::new(cell, digit))
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique: Technique::PointingPair,
+                        cells,
+                        houses: vec![House::Block(block), line],
+                        placements: Vec::new(),
+                        eliminations,
+                        detail: StepDetail::None,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// When the candidates for a digit in a row or column all lie in one block, the digit
+    /// can be removed from the rest of that block.
+    pub(crate) fn find_box_line_reduction(&self) -> Option<Step> {
+        for line in (0..9).map(House::Row).chain((0..9).map(House::Column)) {
+            for digit in 1..=9 {
+                let cells = self.cells_with_candidate(line, digit);
+                if cells.len() < 2
+                    || !cells
+                        .iter()
+                        .all(|cell| block_of(*cell) == block_of(cells[0]))
+                {
+                    continue;
+                }
+                let block = House::Block(block_of(cells[0]));
+                let eliminations: Vec<Candidate> = self
+                    .cells_with_candidate(block, digit)
+                    .into_iter()
+                    .filter(|cell| !cells.contains(cell))
+                    .map(|cell| Candidate::new(cell, digit))
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique: Technique::BoxLineReduction,
+                        cells,
+                        houses: vec![line, block],
+                        placements: Vec::new(),
+                        eliminations,
+                        detail: StepDetail::None,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::logic::{cell_index, Candidate, House};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn find_pointing_pair() {
+        // The 1s in the top left block can only go in its first row, since the other
+        // cells of the block are filled.
+        let sudoku = Sudoku::create_board(
+            "000000000\
+             234000000\
+             567000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000"
+                .to_string(),
+        )
+        .unwrap();
+        let step = sudoku.candidate_grid().find_pointing_pair().unwrap();
+        assert_eq!(step.houses, vec![House::Block(0), House::Row(0)]);
+        assert!(step
+            .eliminations
+            .contains(&Candidate::new(cell_index(0, 6), 1)));
+        assert!(step
+            .eliminations
+            .iter()
+            .all(|e| e.digit == 1 && e.cell / 9 == 0));
+        assert_eq!(step.eliminations.len(), 6);
+    }
+
+    #[test]
+    fn find_box_line_reduction() {
+        // The 1s in the first row can only go in the top left block, since the other
+        // cells of the row are filled.
+        let sudoku = Sudoku::create_board(
+            "000234567\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000"
+                .to_string(),
+        )
+        .unwrap();
+        let step = sudoku.candidate_grid().find_box_line_reduction().unwrap();
+        assert_eq!(step.houses, vec![House::Row(0), House::Block(0)]);
+        assert_eq!(
+            step.eliminations,
+            (1..3)
+                .flat_map(|row| (0..3).map(move |column| Candidate::new(cell_index(row, column), 1)))
+                .collect::<Vec<Candidate>>()
+        );
+    }
+}
@@ -0,0 +1,879 @@
+//! Human style solving: instead of guessing and backtracking, repeatedly look for a
+//! deduction that a person could make on the pencil marks and apply it, recording every
+//! step on the way.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use crate::sudoku::Sudoku;
+
+mod als;
+mod chains;
+mod fish;
+mod forcing;
+mod hint;
+mod intersections;
+mod rating;
+mod singles;
+mod subsets;
+mod uniqueness;
+mod wings;
+
+pub use als::AlmostLockedSet;
+pub use chains::{is_weak_link, Chain, InferenceGraph, DEFAULT_CHAIN_LENGTH};
+pub use forcing::{Derivation, DEFAULT_FORCING_DEPTH};
+pub use hint::{Hint, HintLevel};
+pub use rating::{Difficulty, Rating};
+
+/// A row, column or block, numbered from 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum House {
+    Row(usize),
+    Column(usize),
+    Block(usize),
+}
+
+impl House {
+    /// All 27 houses: the rows, then the columns, then the blocks.
+    pub fn all() -> impl Iterator<Item = House> {
+        (0..9)
+            .map(House::Row)
+            .chain((0..9).map(House::Column))
+            .chain((0..9).map(House::Block))
+    }
+
+    /// The indices of the nine cells in the house, see [`cell_index`].
+    pub fn cells(&self) -> [usize; 9] {
+        let mut cells = [0; 9];
+        for (i, cell) in cells.iter_mut().enumerate() {
+            *cell = match self {
+                House::Row(row) => row * 9 + i,
+                House::Column(column) => i * 9 + column,
+                House::Block(block) => ((block / 3) * 3 + i / 3) * 9 + (block % 3) * 3 + i % 3,
+            };
+        }
+        cells
+    }
+}
+
+impl fmt::Display for House {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            House::Row(row) => write!(f, "r{}", row + 1),
+            House::Column(column) => write!(f, "c{}", column + 1),
+            House::Block(block) => write!(f, "b{}", block + 1),
+        }
+    }
+}
+
+/// The index of a cell in the flat 81 cell layout, counting row by row.
+pub fn cell_index(row: usize, column: usize) -> usize {
+    row * 9 + column
+}
+
+pub(crate) fn row_of(cell: usize) -> usize {
+    cell / 9
+}
+
+pub(crate) fn column_of(cell: usize) -> usize {
+    cell % 9
+}
+
+pub(crate) fn block_of(cell: usize) -> usize {
+    (row_of(cell) / 3) * 3 + column_of(cell) / 3
+}
+
+/// The 20 cells that share a row, column or block with a cell.
+pub(crate) fn peers(cell: usize) -> &'static [usize; 20] {
+    static PEERS: OnceLock<[[usize; 20]; 81]> = OnceLock::new();
+    let peers = PEERS.get_or_init(|| {
+        let mut peers = [[0; 20]; 81];
+        for (cell, cell_peers) in peers.iter_mut().enumerate() {
+            let mut count = 0;
+            for other in 0..81 {
+                if other != cell && sees(cell, other) {
+                    cell_peers[count] = other;
+                    count += 1;
+                }
+            }
+        }
+        peers
+    });
+    &peers[cell]
+}
+
+/// Whether two different cells share a row, column or block.
+pub(crate) fn sees(cell: usize, other: usize) -> bool {
+    row_of(cell) == row_of(other)
+        || column_of(cell) == column_of(other)
+        || block_of(cell) == block_of(other)
+}
+
+/// Format a cell as `r1c1`, with rows and columns counted from 1.
+pub(crate) fn cell_name(cell: usize) -> String {
+    format!("r{}c{}", row_of(cell) + 1, column_of(cell) + 1)
+}
+
+/// All ways to choose `size` items from a slice, keeping their order.
+pub(crate) fn combinations<T: Copy>(items: &[T], size: usize) -> Vec<Vec<T>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[index + 1..], size - 1) {
+            rest.insert(0, *item);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+/// A digit in a cell, either a value that is placed or a pencil mark that is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Candidate {
+    pub cell: usize,
+    pub digit: u8,
+}
+
+impl Candidate {
+    pub fn new(cell: usize, digit: u8) -> Self {
+        Candidate { cell, digit }
+    }
+}
+
+/// The solving techniques, from easiest to hardest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Technique {
+    HiddenSingle,
+    NakedSingle,
+    /// The candidates for a digit in a block all lie in one row or column, two or
+    /// three of them.
+    PointingPair,
+    /// The candidates for a digit in a row or column all lie in one block.
+    BoxLineReduction,
+    NakedPair,
+    /// Two rows whose candidates for a digit lie in the same two columns, or the other
+    /// way around.
+    XWing,
+    HiddenPair,
+    NakedTriple,
+    Swordfish,
+    HiddenTriple,
+    /// A bivalue pivot with two bivalue pincers it sees, all three sharing one digit
+    /// pairwise.
+    XYWing,
+    /// An XY-Wing whose pivot also holds the digit the pincers share.
+    XYZWing,
+    /// Two cells with the same two candidates, joined by a strong link on one of them.
+    WWing,
+    /// Four cells with four digits between them, where only one digit is not confined to
+    /// cells that see each other.
+    WXYZWing,
+    /// Three corners of a rectangle over two blocks hold only the same two digits, so
+    /// the fourth corner can not be either of them without a second solution.
+    UniqueRectangleType1,
+    /// The two extra corners of a rectangle share one extra digit, which must be placed
+    /// in one of them.
+    UniqueRectangleType2,
+    /// The extra digits of two corners of a rectangle form a naked subset with other
+    /// cells of a house.
+    UniqueRectangleType3,
+    /// One of the rectangle digits is locked to the two extra corners in a house, so the
+    /// other one can be removed from them.
+    UniqueRectangleType4,
+    NakedQuad,
+    Jellyfish,
+    HiddenQuad,
+    /// Bivalue Universal Grave plus one: every empty cell has two candidates except one
+    /// with three, so that cell takes the digit that would otherwise leave two solutions.
+    BugPlusOne,
+    /// An X-Wing with extra candidates, the fins, in one block.
+    FinnedXWing,
+    /// A finned X-Wing where one of the base lines has a single candidate left besides
+    /// the fins.
+    SashimiXWing,
+    FinnedSwordfish,
+    SashimiSwordfish,
+    FinnedJellyfish,
+    SashimiJellyfish,
+    /// A chain on one digit, linking the places for it in rows, columns and blocks.
+    XChain,
+    /// A chain through cells with two candidates.
+    XYChain,
+    AlternatingInferenceChain,
+    /// Two almost locked sets joined by a restricted common digit.
+    AlsXz,
+    /// Two almost locked sets that each share a restricted common with a third one.
+    AlsXyWing,
+    /// A cell with an almost locked set for each of its candidates.
+    DeathBlossom,
+    /// Placing a candidate and following the singles of its digit leads to a
+    /// contradiction.
+    Nishio,
+    /// Placing a candidate and following all singles leads to a contradiction.
+    ContradictionChain,
+    /// Whatever value a cell takes, the singles after it agree on a placement or
+    /// elimination.
+    CellForcingChain,
+    /// Wherever a digit goes in a house, the singles after it agree on a placement or
+    /// elimination.
+    UnitForcingChain,
+}
+
+impl Technique {
+    /// Every technique in the order the logical solver tries them.
+    pub const ALL: [Technique; 38] = [
+        Technique::HiddenSingle,
+        Technique::NakedSingle,
+        Technique::PointingPair,
+        Technique::BoxLineReduction,
+        Technique::NakedPair,
+        Technique::XWing,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::Swordfish,
+        Technique::HiddenTriple,
+        Technique::XYWing,
+        Technique::XYZWing,
+        Technique::WWing,
+        Technique::WXYZWing,
+        Technique::UniqueRectangleType1,
+        Technique::UniqueRectangleType2,
+        Technique::UniqueRectangleType3,
+        Technique::UniqueRectangleType4,
+        Technique::NakedQuad,
+        Technique::Jellyfish,
+        Technique::HiddenQuad,
+        Technique::BugPlusOne,
+        Technique::FinnedXWing,
+        Technique::SashimiXWing,
+        Technique::FinnedSwordfish,
+        Technique::SashimiSwordfish,
+        Technique::FinnedJellyfish,
+        Technique::SashimiJellyfish,
+        Technique::XChain,
+        Technique::XYChain,
+        Technique::AlternatingInferenceChain,
+        Technique::AlsXz,
+        Technique::AlsXyWing,
+        Technique::DeathBlossom,
+        Technique::Nishio,
+        Technique::ContradictionChain,
+        Technique::CellForcingChain,
+        Technique::UnitForcingChain,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::HiddenSingle => "Hidden Single",
+            Technique::NakedSingle => "Naked Single",
+            Technique::PointingPair => "Pointing Pair",
+            Technique::BoxLineReduction => "Box/Line Reduction",
+            Technique::NakedPair => "Naked Pair",
+            Technique::HiddenPair => "Hidden Pair",
+            Technique::NakedTriple => "Naked Triple",
+            Technique::HiddenTriple => "Hidden Triple",
+            Technique::NakedQuad => "Naked Quad",
+            Technique::HiddenQuad => "Hidden Quad",
+            Technique::XWing => "X-Wing",
+            Technique::Swordfish => "Swordfish",
+            Technique::Jellyfish => "Jellyfish",
+            Technique::FinnedXWing => "Finned X-Wing",
+            Technique::SashimiXWing => "Sashimi X-Wing",
+            Technique::FinnedSwordfish => "Finned Swordfish",
+            Technique::SashimiSwordfish => "Sashimi Swordfish",
+            Technique::FinnedJellyfish => "Finned Jellyfish",
+            Technique::SashimiJellyfish => "Sashimi Jellyfish",
+            Technique::XYWing => "XY-Wing",
+            Technique::XYZWing => "XYZ-Wing",
+            Technique::WWing => "W-Wing",
+            Technique::WXYZWing => "WXYZ-Wing",
+            Technique::XChain => "X-Chain",
+            Technique::XYChain => "XY-Chain",
+            Technique::AlternatingInferenceChain => "Alternating Inference Chain",
+            Technique::UniqueRectangleType1 => "Unique Rectangle Type 1",
+            Technique::UniqueRectangleType2 => "Unique Rectangle Type 2",
+            Technique::UniqueRectangleType3 => "Unique Rectangle Type 3",
+            Technique::UniqueRectangleType4 => "Unique Rectangle Type 4",
+            Technique::BugPlusOne => "BUG+1",
+            Technique::AlsXz => "ALS-XZ",
+            Technique::AlsXyWing => "ALS-XY-Wing",
+            Technique::DeathBlossom => "Death Blossom",
+            Technique::Nishio => "Nishio",
+            Technique::ContradictionChain => "Contradiction Forcing Chain",
+            Technique::CellForcingChain => "Cell Forcing Chain",
+            Technique::UnitForcingChain => "Unit Forcing Chain",
+        }
+    }
+
+    /// Whether the technique is only sound for a sudoku with a single solution.
+    pub fn requires_unique_solution(&self) -> bool {
+        matches!(
+            self,
+            Technique::UniqueRectangleType1
+                | Technique::UniqueRectangleType2
+                | Technique::UniqueRectangleType3
+                | Technique::UniqueRectangleType4
+                | Technique::BugPlusOne
+        )
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// One deduction: which technique found it, the cells and houses that form the pattern,
+/// and the values it places and the candidates it removes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub technique: Technique,
+    pub cells: Vec<usize>,
+    pub houses: Vec<House>,
+    pub placements: Vec<Candidate>,
+    pub eliminations: Vec<Candidate>,
+    pub detail: StepDetail,
+}
+
+/// The parts of a pattern that the cells and houses of a [`Step`] do not capture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepDetail {
+    None,
+    /// A fish on one digit: every candidate of the digit in the base sets, apart from
+    /// the fins, lies in the cover sets.
+    Fish {
+        digit: u8,
+        base_sets: Vec<House>,
+        cover_sets: Vec<House>,
+        fins: Vec<usize>,
+    },
+    /// A wing: pincers that each see the pivot, and together force the digit out of every
+    /// cell that sees all of them. The pivot is a single cell, except for a W-Wing where
+    /// it is the two ends of the strong link that joins the pincers.
+    Wing {
+        pivot: Vec<usize>,
+        pincers: Vec<usize>,
+        digit: u8,
+    },
+    /// An alternating inference chain, see [`Chain`].
+    Chain(Chain),
+    /// A unique rectangle: four corners in two rows, two columns and two blocks that all
+    /// hold the two digits.
+    UniqueRectangle {
+        corners: Vec<usize>,
+        digits: [u8; 2],
+    },
+    /// Almost locked sets and the restricted common digits that join them. For a Death
+    /// Blossom the stem is the cell whose candidates each have a petal among the sets.
+    AlmostLockedSets {
+        sets: Vec<AlmostLockedSet>,
+        restricted_commons: Vec<u8>,
+        stem: Option<usize>,
+    },
+    /// The trial placements of a forcing chain and what followed from each of them.
+    Forcing {
+        derivations: Vec<Derivation>,
+    },
+}
+
+fn house_list(houses: &[House]) -> String {
+    let names: Vec<String> = houses.iter().map(|h| h.to_string()).collect();
+    names.join(",")
+}
+
+fn cell_list(cells: &[usize]) -> String {
+    let names: Vec<String> = cells.iter().map(|c| cell_name(*c)).collect();
+    names.join(",")
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.technique)?;
+        match &self.detail {
+            StepDetail::None => {
+                if !self.houses.is_empty() {
+                    write!(f, " in {}", house_list(&self.houses))?;
+                }
+                if !self.cells.is_empty() && self.placements.is_empty() {
+                    write!(f, " ({})", cell_list(&self.cells))?;
+                }
+            }
+            StepDetail::Fish {
+                digit,
+                base_sets,
+                cover_sets,
+                fins,
+            } => {
+                write!(
+                    f,
+                    " on {digit} in {} over {}",
+                    house_list(base_sets),
+                    house_list(cover_sets)
+                )?;
+                if !fins.is_empty() {
+                    write!(f, " with fins {}", cell_list(fins))?;
+                }
+            }
+            StepDetail::Wing { pivot, pincers, .. } => {
+                write!(
+                    f,
+                    " with pivot {} and pincers {}",
+                    cell_list(pivot),
+                    cell_list(pincers)
+                )?;
+            }
+            StepDetail::Chain(chain) => write!(f, " {chain}")?,
+            StepDetail::UniqueRectangle { corners, digits } => {
+                write!(
+                    f,
+                    " on {}/{} in {}",
+                    digits[0],
+                    digits[1],
+                    cell_list(corners)
+                )?;
+            }
+            StepDetail::AlmostLockedSets {
+                sets,
+                restricted_commons,
+                stem,
+            } => {
+                let sets: Vec<String> = sets.iter().map(|set| set.to_string()).collect();
+                match stem {
+                    Some(stem) => write!(
+                        f,
+                        " with stem {} and petals {}",
+                        cell_name(*stem),
+                        sets.join("; ")
+                    )?,
+                    None => {
+                        let commons: Vec<String> =
+                            restricted_commons.iter().map(|d| d.to_string()).collect();
+                        write!(f, " {} via {}", sets.join("; "), commons.join(","))?
+                    }
+                }
+            }
+            StepDetail::Forcing { derivations } => {
+                let derivations: Vec<String> = derivations.iter().map(|d| d.to_string()).collect();
+                write!(f, " {}", derivations.join(" | "))?;
+            }
+        }
+        let mut effects: Vec<String> = Vec::new();
+        for placement in self.placements.iter() {
+            effects.push(format!("{}={}", cell_name(placement.cell), placement.digit));
+        }
+        for elimination in self.eliminations.iter() {
+            effects.push(format!(
+                "{}<>{}",
+                cell_name(elimination.cell),
+                elimination.digit
+            ));
+        }
+        write!(f, ": {}", effects.join(", "))
+    }
+}
+
+/// The values of a board together with the pencil marks of its empty cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidateGrid {
+    values: [u8; 81],
+    candidates: [u16; 81],
+    unique_solution: bool,
+}
+
+impl Sudoku {
+    /// The pencil marks of the board: for each empty cell the values that
+    /// [`Sudoku::check_possible_values`] allows.
+    pub fn candidate_grid(&self) -> CandidateGrid {
+        let mut grid = CandidateGrid {
+            values: self.board,
+            candidates: [0; 81],
+            unique_solution: false,
+        };
+        for cell in 0..81 {
+            if self.board[cell] == 0 {
+                grid.candidates[cell] = self.candidates(row_of(cell), column_of(cell));
+            }
+        }
+        grid
+    }
+
+    /// Solve the sudoku with logic only, see [`CandidateGrid::solve`]. The uniqueness
+    /// techniques are used when the solution counter confirms the sudoku has a single
+    /// solution.
+    pub fn solve_logically(&self) -> LogicalSolution {
+        let mut grid = self.candidate_grid();
+        if self.has_unique_solution() {
+            grid.assume_unique_solution();
+        }
+        grid.solve()
+    }
+}
+
+impl CandidateGrid {
+    /// Allow the techniques that rely on the sudoku having a single solution, see
+    /// [`Technique::requires_unique_solution`]. The caller asserts that it does; on a
+    /// sudoku with several solutions these techniques can remove correct candidates.
+    pub fn assume_unique_solution(&mut self) {
+        self.unique_solution = true;
+    }
+
+    pub fn assumes_unique_solution(&self) -> bool {
+        self.unique_solution
+    }
+
+    /// Apply the easiest technique that makes progress until the board is solved or no
+    /// technique applies any more.
+    pub fn solve(self) -> LogicalSolution {
+        let mut grid = self;
+        let mut steps = Vec::new();
+        while !grid.is_solved() && !grid.has_contradiction() {
+            match grid.next_step() {
+                Some(step) => {
+                    grid.apply(&step);
+                    steps.push(step);
+                }
+                None => break,
+            }
+        }
+        LogicalSolution { steps, grid }
+    }
+
+    /// The value in a cell, `0` if it is empty.
+    pub fn value(&self, cell: usize) -> u8 {
+        self.values[cell]
+    }
+
+    /// Bit mask of the pencil marks of a cell, `0` for a filled cell.
+    pub fn candidates(&self, cell: usize) -> u16 {
+        self.candidates[cell]
+    }
+
+    pub fn has_candidate(&self, cell: usize, digit: u8) -> bool {
+        self.candidates[cell] & (1 << (digit - 1)) != 0
+    }
+
+    /// The cells of a house that have the digit as a pencil mark.
+    pub(crate) fn cells_with_candidate(&self, house: House, digit: u8) -> Vec<usize> {
+        house
+            .cells()
+            .into_iter()
+            .filter(|cell| self.has_candidate(*cell, digit))
+            .collect()
+    }
+
+    /// Whether the digit is already placed somewhere in the house.
+    pub(crate) fn is_placed(&self, house: House, digit: u8) -> bool {
+        house.cells().iter().any(|cell| self.values[*cell] == digit)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        !self.values.contains(&0)
+    }
+
+    /// Whether the pencil marks show the board can not be solved: an empty cell without
+    /// candidates, or a digit that fits nowhere in a house.
+    pub fn has_contradiction(&self) -> bool {
+        if (0..81).any(|cell| self.values[cell] == 0 && self.candidates[cell] == 0) {
+            return true;
+        }
+        House::all().any(|house| {
+            (1..=9).any(|digit| {
+                !self.is_placed(house, digit) && self.cells_with_candidate(house, digit).is_empty()
+            })
+        })
+    }
+
+    /// Fill a cell and remove the digit from the pencil marks of its peers.
+    pub(crate) fn place(&mut self, cell: usize, digit: u8) {
+        self.values[cell] = digit;
+        self.candidates[cell] = 0;
+        for peer in peers(cell) {
+            self.candidates[*peer] &= !(1 << (digit - 1));
+        }
+    }
+
+    /// The candidates for a digit, outside the given cells, in cells that see every one of
+    /// them.
+    pub(crate) fn candidates_seeing_all(&self, cells: &[usize], digit: u8) -> Vec<Candidate> {
+        (0..81)
+            .filter(|cell| self.has_candidate(*cell, digit) && !cells.contains(cell))
+            .filter(|cell| cells.iter().all(|other| sees(*cell, *other)))
+            .map(|cell| Candidate::new(cell, digit))
+            .collect()
+    }
+
+    pub(crate) fn eliminate(&mut self, cell: usize, digit: u8) {
+        self.candidates[cell] &= !(1 << (digit - 1));
+    }
+
+    /// Carry out the placements and eliminations of a step.
+    pub fn apply(&mut self, step: &Step) {
+        for placement in step.placements.iter() {
+            self.place(placement.cell, placement.digit);
+        }
+        for elimination in step.eliminations.iter() {
+            self.eliminate(elimination.cell, elimination.digit);
+        }
+    }
+
+    /// The board with the values placed so far.
+    pub fn to_sudoku(&self) -> Sudoku {
+        let mut sudoku = Sudoku::create_board("0".repeat(81)).unwrap();
+        for cell in 0..81 {
+            if self.values[cell] != 0 {
+                sudoku.place(row_of(cell), column_of(cell), self.values[cell]);
+            }
+        }
+        sudoku
+    }
+
+    /// The first step the technique finds on the current pencil marks, if any. The
+    /// uniqueness techniques find nothing unless the grid assumes a unique solution.
+    pub fn find_step(&self, technique: Technique) -> Option<Step> {
+        if technique.requires_unique_solution() && !self.unique_solution {
+            return None;
+        }
+        match technique {
+            Technique::HiddenSingle => self.find_hidden_single(),
+            Technique::NakedSingle => self.find_naked_single(),
+            Technique::PointingPair => self.find_pointing_pair(),
+            Technique::BoxLineReduction => self.find_box_line_reduction(),
+            Technique::NakedPair => self.find_naked_subset(2),
+            Technique::NakedTriple => self.find_naked_subset(3),
+            Technique::NakedQuad => self.find_naked_subset(4),
+            Technique::HiddenPair => self.find_hidden_subset(2),
+            Technique::HiddenTriple => self.find_hidden_subset(3),
+            Technique::HiddenQuad => self.find_hidden_subset(4),
+            Technique::XWing => self.find_fish(2),
+            Technique::Swordfish => self.find_fish(3),
+            Technique::Jellyfish => self.find_fish(4),
+            Technique::FinnedXWing => self.find_finned_fish(2, false),
+            Technique::FinnedSwordfish => self.find_finned_fish(3, false),
+            Technique::FinnedJellyfish => self.find_finned_fish(4, false),
+            Technique::SashimiXWing => self.find_finned_fish(2, true),
+            Technique::SashimiSwordfish => self.find_finned_fish(3, true),
+            Technique::SashimiJellyfish => self.find_finned_fish(4, true),
+            Technique::XYWing => self.find_xy_wing(),
+            Technique::XYZWing => self.find_xyz_wing(),
+            Technique::WWing => self.find_w_wing(),
+            Technique::WXYZWing => self.find_wxyz_wing(),
+            Technique::UniqueRectangleType1 => self.find_unique_rectangle(1),
+            Technique::UniqueRectangleType2 => self.find_unique_rectangle(2),
+            Technique::UniqueRectangleType3 => self.find_unique_rectangle(3),
+            Technique::UniqueRectangleType4 => self.find_unique_rectangle(4),
+            Technique::BugPlusOne => self.find_bug_plus_one(),
+            Technique::AlsXz => self.find_als_xz(),
+            Technique::AlsXyWing => self.find_als_xy_wing(),
+            Technique::DeathBlossom => self.find_death_blossom(),
+            Technique::Nishio
+            | Technique::ContradictionChain
+            | Technique::CellForcingChain
+            | Technique::UnitForcingChain => {
+                self.find_forcing_chain(technique, DEFAULT_FORCING_DEPTH)
+            }
+            Technique::XChain | Technique::XYChain | Technique::AlternatingInferenceChain => {
+                self.find_chain(technique, DEFAULT_CHAIN_LENGTH)
+            }
+        }
+    }
+
+    /// The step of the easiest technique that applies.
+    pub fn next_step(&self) -> Option<Step> {
+        Technique::ALL
+            .iter()
+            .find_map(|technique| self.find_step(*technique))
+    }
+}
+
+/// The result of [`Sudoku::solve_logically`] and [`CandidateGrid::solve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogicalSolution {
+    /// Every step taken, in order.
+    pub steps: Vec<Step>,
+    /// The board and pencil marks after the last step.
+    pub grid: CandidateGrid,
+}
+
+impl LogicalSolution {
+    pub fn is_solved(&self) -> bool {
+        self.grid.is_solved()
+    }
+}
+
+/// Fixtures shared by the tests of the techniques.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use crate::sudoku::logic::{cell_index, column_of, row_of, CandidateGrid, Step};
+    use crate::sudoku::solver::SolverAlgorithm;
+    use crate::sudoku::Sudoku;
+
+    /// An empty board where the given cells only keep the given candidates.
+    pub(crate) fn grid_with(cells: &[(usize, usize, &[u8])]) -> CandidateGrid {
+        let mut grid = Sudoku::create_board("0".repeat(81))
+            .unwrap()
+            .candidate_grid();
+        for (row, column, keep) in cells {
+            for digit in 1..=9 {
+                if !keep.contains(&digit) {
+                    grid.eliminate(cell_index(*row, *column), digit);
+                }
+            }
+        }
+        grid
+    }
+
+    /// An empty board where the digit is limited to the given columns of the given rows.
+    pub(crate) fn grid_with_places(digit: u8, rows: &[(usize, &[usize])]) -> CandidateGrid {
+        let mut grid = Sudoku::create_board("0".repeat(81))
+            .unwrap()
+            .candidate_grid();
+        for (row, columns) in rows {
+            for column in 0..9 {
+                if !columns.contains(&column) {
+                    grid.eliminate(cell_index(*row, column), digit);
+                }
+            }
+        }
+        grid
+    }
+
+    /// Check every placement and elimination of the steps against the solution found by
+    /// backtracking.
+    pub(crate) fn assert_steps_agree_with_backtracking<'a>(
+        sudoku: &Sudoku,
+        steps: impl IntoIterator<Item = &'a Step>,
+    ) {
+        let mut backtracked = sudoku.clone();
+        backtracked.solve_sudoku(&SolverAlgorithm::MostRestricted);
+        let answer = backtracked.to_array();
+        let digit = |cell: usize| answer[row_of(cell)][column_of(cell)];
+        for step in steps {
+            for placement in step.placements.iter() {
+                assert_eq!(digit(placement.cell), placement.digit, "{step}");
+            }
+            for elimination in step.eliminations.iter() {
+                assert_ne!(digit(elimination.cell), elimination.digit, "{step}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::digits;
+    use crate::sudoku::logic::test_support::assert_steps_agree_with_backtracking;
+
+    #[test]
+    fn house_cells() {
+        assert_eq!(House::Row(1).cells(), [9, 10, 11, 12, 13, 14, 15, 16, 17]);
+        assert_eq!(
+            House::Column(2).cells(),
+            [2, 11, 20, 29, 38, 47, 56, 65, 74]
+        );
+        assert_eq!(
+            House::Block(4).cells(),
+            [30, 31, 32, 39, 40, 41, 48, 49, 50]
+        );
+        assert_eq!(peers(0).len(), 20);
+        assert!(peers(40).contains(&30) && !peers(40).contains(&0));
+    }
+
+    #[test]
+    fn candidate_grid_matches_possible_values() {
+        let sudoku = Sudoku::create_board(
+            "530070000\
+             600195000\
+             098000060\
+             800060003\
+             400803001\
+             700020006\
+             060000280\
+             000419005\
+             000080079"
+                .to_string(),
+        )
+        .unwrap();
+        let grid = sudoku.candidate_grid();
+        for row in 0..9 {
+            for column in 0..9 {
+                let cell = cell_index(row, column);
+                if grid.value(cell) != 0 {
+                    assert_eq!(grid.candidates(cell), 0);
+                    continue;
+                }
+                let marks: Vec<char> = digits(grid.candidates(cell))
+                    .map(|digit| (b'0' + digit) as char)
+                    .collect();
+                assert_eq!(marks, sudoku.check_possible_values(row, column));
+            }
+        }
+        assert_eq!(grid.to_sudoku(), sudoku);
+    }
+
+    #[test]
+    fn solve_easy_sudoku_with_singles() {
+        let sudoku = Sudoku::create_board(
+            "530070000\
+             600195000\
+             098000060\
+             800060003\
+             400803001\
+             700020006\
+             060000280\
+             000419005\
+             000080079"
+                .to_string(),
+        )
+        .unwrap();
+        let solution = sudoku.solve_logically();
+        assert!(solution.is_solved());
+        assert!(solution.grid.to_sudoku().check_sudoku_completed());
+        assert_eq!(solution.steps.len(), 51);
+        assert!(solution
+            .steps
+            .iter()
+            .all(|step| step.technique <= Technique::NakedSingle));
+    }
+
+    #[test]
+    fn solve_with_subsets_and_intersections() {
+        let sudoku = Sudoku::create_board(
+            "478600000\
+             000900006\
+             000804000\
+             500037000\
+             030040080\
+             004000120\
+             090000062\
+             000000700\
+             200700010"
+                .to_string(),
+        )
+        .unwrap();
+        let solution = sudoku.solve_logically();
+        assert!(solution.is_solved());
+        let techniques: Vec<Technique> = solution.steps.iter().map(|s| s.technique).collect();
+        assert!(techniques.contains(&Technique::BoxLineReduction));
+        assert!(techniques.contains(&Technique::NakedPair));
+        assert!(techniques.contains(&Technique::HiddenPair));
+
+        assert_steps_agree_with_backtracking(&sudoku, solution.steps.iter());
+    }
+
+    #[test]
+    fn describe_step() {
+        let step = Step {
+            technique: Technique::NakedPair,
+            cells: vec![cell_index(2, 0), cell_index(2, 4)],
+            houses: vec![House::Row(2)],
+            placements: Vec::new(),
+            eliminations: vec![Candidate::new(cell_index(2, 1), 2)],
+            detail: StepDetail::None,
+        };
+        assert_eq!(step.to_string(), "Naked Pair in r3 (r3c1,r3c5): r3c2<>2");
+    }
+}
@@ -0,0 +1,292 @@
+use std::fmt;
+
+use crate::sudoku::logic::{LogicalSolution, Technique};
+use crate::sudoku::Sudoku;
+
+/// The difficulty bucket of a sudoku, decided by the hardest step it needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Difficulty {
+    /// Singles only.
+    Easy,
+    /// Intersections, pairs and X-Wings.
+    Medium,
+    /// Triples, Swordfish, wings and unique rectangles.
+    Hard,
+    /// Quads, Jellyfish, finned fish and single digit or bivalue chains.
+    Expert,
+    /// Alternating inference chains, almost locked sets and forcing chains, or more than
+    /// the logical solver knows.
+    Extreme,
+}
+
+impl Difficulty {
+    /// The bucket for the score of the hardest step.
+    pub fn from_score(score: f32) -> Self {
+        if score <= 2.3 {
+            Difficulty::Easy
+        } else if score <= 3.4 {
+            Difficulty::Medium
+        } else if score <= 4.6 {
+            Difficulty::Hard
+        } else if score <= 6.6 {
+            Difficulty::Expert
+        } else {
+            Difficulty::Extreme
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+            Difficulty::Extreme => "extreme",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Technique {
+    /// The score of a step with the technique, on the scale of Sudoku Explainer where a
+    /// hidden single is 1.5 and forcing chains start around 7.5. The scores never go down
+    /// along [`Technique::ALL`], so the easiest technique is also the cheapest one.
+    pub fn difficulty(&self) -> f32 {
+        match self {
+            Technique::HiddenSingle => 1.5,
+            Technique::NakedSingle => 2.3,
+            Technique::PointingPair => 2.6,
+            Technique::BoxLineReduction => 2.8,
+            Technique::NakedPair => 3.0,
+            Technique::XWing => 3.2,
+            Technique::HiddenPair => 3.4,
+            Technique::NakedTriple => 3.6,
+            Technique::Swordfish => 3.8,
+            Technique::HiddenTriple => 4.0,
+            Technique::XYWing => 4.2,
+            Technique::XYZWing | Technique::WWing => 4.4,
+            Technique::WXYZWing | Technique::UniqueRectangleType1 => 4.5,
+            Technique::UniqueRectangleType2
+            | Technique::UniqueRectangleType3
+            | Technique::UniqueRectangleType4 => 4.6,
+            Technique::NakedQuad => 5.0,
+            Technique::Jellyfish => 5.2,
+            Technique::HiddenQuad => 5.4,
+            Technique::BugPlusOne => 5.6,
+            Technique::FinnedXWing => 5.7,
+            Technique::SashimiXWing => 5.8,
+            Technique::FinnedSwordfish => 5.9,
+            Technique::SashimiSwordfish => 6.0,
+            Technique::FinnedJellyfish => 6.1,
+            Technique::SashimiJellyfish => 6.2,
+            Technique::XChain => 6.5,
+            Technique::XYChain => 6.6,
+            Technique::AlternatingInferenceChain => 7.0,
+            Technique::AlsXz => 7.1,
+            Technique::AlsXyWing => 7.3,
+            Technique::DeathBlossom => 7.5,
+            Technique::Nishio => 7.6,
+            Technique::ContradictionChain => 8.0,
+            Technique::CellForcingChain => 8.3,
+            Technique::UnitForcingChain => 8.5,
+        }
+    }
+}
+
+/// How hard a sudoku is for a person, from the steps of its logical solution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rating {
+    /// The score of the hardest step, `0.0` when no step was needed and
+    /// [`Rating::UNSOLVED`] when the techniques got stuck.
+    pub hardest: f32,
+    /// The scores of all steps added up, so that a long solution weighs more than a
+    /// short one with the same hardest step. Getting stuck adds [`Rating::UNSOLVED`].
+    pub total: f32,
+    /// The technique of the hardest step that was taken. When the techniques got stuck
+    /// the score in [`Rating::hardest`] does not come from this technique but is
+    /// [`Rating::UNSOLVED`]; the technique then only tells how far the steps got.
+    pub hardest_technique: Option<Technique>,
+    /// Whether the techniques were enough to solve the sudoku. When they are not the
+    /// sudoku is rated [`Difficulty::Extreme`], whatever the steps before got stuck.
+    pub solved: bool,
+    pub difficulty: Difficulty,
+}
+
+impl Rating {
+    /// The score of a sudoku the techniques can not finish, above that of every
+    /// technique, so such a sudoku ranks above all sudokus that can be solved.
+    pub const UNSOLVED: f32 = 10.0;
+}
+
+impl fmt::Display for Rating {
+    /// As in `4.2 hard (XY-Wing), total 87.3`. When the techniques got stuck it reads
+    /// `10.0 extreme (not solved), total 16.1`, or `(not solved after XY-Wing)` with
+    /// the hardest technique of the steps taken before it got stuck.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1} {}", self.hardest, self.difficulty)?;
+        match (self.solved, self.hardest_technique) {
+            (true, Some(technique)) => write!(f, " ({technique})")?,
+            (true, None) => {}
+            (false, Some(technique)) => write!(f, " (not solved after {technique})")?,
+            (false, None) => write!(f, " (not solved)")?,
+        }
+        write!(f, ", total {:.1}", self.total)
+    }
+}
+
+impl LogicalSolution {
+    /// Rate the steps of the solution, see [`Rating`].
+    pub fn rating(&self) -> Rating {
+        let hardest_technique = self
+            .steps
+            .iter()
+            .map(|step| step.technique)
+            .max_by(|a, b| a.difficulty().total_cmp(&b.difficulty()));
+        let total = self
+            .steps
+            .iter()
+            .fold(0.0, |total, step| total + step.technique.difficulty());
+        let solved = self.is_solved();
+        let (hardest, total) = if solved {
+            let hardest = hardest_technique.map_or(0.0, |technique| technique.difficulty());
+            (hardest, total)
+        } else {
+            (Rating::UNSOLVED, total + Rating::UNSOLVED)
+        };
+        Rating {
+            hardest,
+            total,
+            hardest_technique,
+            solved,
+            difficulty: Difficulty::from_score(hardest),
+        }
+    }
+}
+
+impl Sudoku {
+    /// Rate how hard the sudoku is by solving it logically, which always applies the
+    /// easiest technique that makes progress, and scoring the steps it took.
+    pub fn rate(&self) -> Rating {
+        self.solve_logically().rating()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::logic::{Difficulty, Rating, Technique};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn difficulty_follows_technique_order() {
+        for pair in Technique::ALL.windows(2) {
+            assert!(pair[0].difficulty() <= pair[1].difficulty(), "{pair:?}");
+        }
+        assert!(Technique::ALL
+            .iter()
+            .all(|technique| technique.difficulty() < Rating::UNSOLVED));
+        assert_eq!(Difficulty::from_score(1.5), Difficulty::Easy);
+        assert_eq!(Difficulty::from_score(3.4), Difficulty::Medium);
+        assert_eq!(Difficulty::from_score(4.2), Difficulty::Hard);
+        assert_eq!(Difficulty::from_score(6.5), Difficulty::Expert);
+        assert_eq!(Difficulty::from_score(8.5), Difficulty::Extreme);
+    }
+
+    #[test]
+    fn rate_sudokus() {
+        let easy = Sudoku::create_board(
+            "530070000\
+             600195000\
+             098000060\
+             800060003\
+             400803001\
+             700020006\
+             060000280\
+             000419005\
+             000080079"
+                .to_string(),
+        )
+        .unwrap();
+        let rating = easy.rate();
+        assert!(rating.solved);
+        assert_eq!(rating.difficulty, Difficulty::Easy);
+        assert!(rating.hardest <= 2.3);
+        assert!(rating.total >= 51.0 * 1.5);
+
+        let harder = Sudoku::create_board(
+            "478600000\
+             000900006\
+             000804000\
+             500037000\
+             030040080\
+             004000120\
+             090000062\
+             000000700\
+             200700010"
+                .to_string(),
+        )
+        .unwrap();
+        let harder_rating = harder.rate();
+        assert!(harder_rating.solved);
+        assert!(harder_rating.difficulty > Difficulty::Easy);
+        assert!(harder_rating.hardest >= Technique::HiddenPair.difficulty());
+        assert!(harder_rating.total > rating.total);
+        assert!(harder_rating.to_string().contains(", total "));
+
+        let solved = Sudoku::create_board(easy.solve_logically().grid.to_sudoku().to_line())
+            .unwrap()
+            .rate();
+        assert_eq!(solved.hardest, 0.0);
+        assert_eq!(solved.hardest_technique, None);
+        assert_eq!(solved.to_string(), "0.0 easy, total 0.0");
+
+        let stuck = Sudoku::create_board(
+            "800000000\
+             003600000\
+             070090200\
+             050007000\
+             000045700\
+             000100030\
+             001000068\
+             008500010\
+             090000400"
+                .to_string(),
+        )
+        .unwrap()
+        .rate();
+        assert!(!stuck.solved);
+        assert_eq!(stuck.difficulty, Difficulty::Extreme);
+        assert!(stuck.hardest > harder_rating.hardest);
+        assert_eq!(stuck.to_string(), "10.0 extreme (not solved), total 10.0");
+
+        let stuck_after_steps = Sudoku::create_board(
+            "100007090\
+             030020008\
+             009600500\
+             005300900\
+             010080002\
+             600004000\
+             300000010\
+             040000007\
+             007000300"
+                .to_string(),
+        )
+        .unwrap()
+        .rate();
+        assert!(!stuck_after_steps.solved);
+        assert_eq!(stuck_after_steps.hardest, Rating::UNSOLVED);
+        assert_eq!(
+            stuck_after_steps.hardest_technique,
+            Some(Technique::FinnedSwordfish)
+        );
+        assert!(stuck_after_steps.total > Rating::UNSOLVED);
+        assert_eq!(
+            stuck_after_steps.to_string(),
+            "10.0 extreme (not solved after Finned Swordfish), total 17.4"
+        );
+    }
+}
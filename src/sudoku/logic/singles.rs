@@ -0,0 +1,77 @@
+use crate::sudoku::digits;
+use crate::sudoku::logic::{Candidate, CandidateGrid, House, Step, StepDetail, Technique};
+
+impl CandidateGrid {
+    /// A cell with a single pencil mark must hold that digit.
+    pub(crate) fn find_naked_single(&self) -> Option<Step> {
+        (0..81)
+            .find(|cell| self.candidates(*cell).count_ones() == 1)
+            .map(|cell| {
+                let digit = digits(self.candidates(cell)).next().unwrap();
+                Step {
+                    technique: Technique::NakedSingle,
+                    cells: vec![cell],
+                    houses: Vec::new(),
+                    placements: vec![Candidate::new(cell, digit)],
+                    eliminations: Vec::new(),
+                    detail: StepDetail::None,
+                }
+            })
+    }
+
+    /// A digit that fits in only one cell of a house must go there.
+    pub(crate) fn find_hidden_single(&self) -> Option<Step> {
+        House::all().find_map(|house| (1..=9).find_map(|digit| self.hidden_single_in(house, digit)))
+    }
+
+    /// The hidden single of a digit in a house, if the digit fits in only one of its cells.
+    pub(crate) fn hidden_single_in(&self, house: House, digit: u8) -> Option<Step> {
+        let cells = self.cells_with_candidate(house, digit);
+        if cells.len() != 1 || self.is_placed(house, digit) {
+            return None;
+        }
+        Some(Step {
+            technique: Technique::HiddenSingle,
+            cells: cells.clone(),
+            houses: vec![house],
+            placements: vec![Candidate::new(cells[0], digit)],
+            eliminations: Vec::new(),
+            detail: StepDetail::None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::logic::{cell_index, Candidate, House, Technique};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn find_singles() {
+        let sudoku = Sudoku::create_board(
+            "012345678\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000"
+                .to_string(),
+        )
+        .unwrap();
+        let grid = sudoku.candidate_grid();
+        let naked_single = grid.find_naked_single().unwrap();
+        assert_eq!(naked_single.technique, Technique::NakedSingle);
+        assert_eq!(naked_single.placements, vec![Candidate::new(0, 9)]);
+
+        let hidden_single = grid.find_hidden_single().unwrap();
+        assert_eq!(hidden_single.technique, Technique::HiddenSingle);
+        assert_eq!(hidden_single.houses, vec![House::Row(0)]);
+        assert_eq!(
+            hidden_single.placements,
+            vec![Candidate::new(cell_index(0, 0), 9)]
+        );
+    }
+}
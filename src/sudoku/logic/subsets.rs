@@ -0,0 +1,171 @@
+use crate::sudoku::digits;
+use crate::sudoku::logic::{
+    combinations, Candidate, CandidateGrid, House, Step, StepDetail, Technique,
+};
+
+impl CandidateGrid {
+    /// When `size` cells of a house have only `size` digits between them, those digits
+    /// are used up by these cells and can be removed from the rest of the house.
+    pub(crate) fn find_naked_subset(&self, size: usize) -> Option<Step> {
+        let technique = match size {
+            2 => Technique::NakedPair,
+            3 => Technique::NakedTriple,
+            _ => Technique::NakedQuad,
+        };
+        for house in House::all() {
+            let open_cells: Vec<usize> = house
+                .cells()
+                .into_iter()
+                .filter(|cell| {
+                    let count = self.candidates(*cell).count_ones() as usize;
+                    count >= 2 && count <= size
+                })
+                .collect();
+            for subset in combinations(&open_cells, size) {
+                let union = subset
+                    .iter()
+                    .fold(0, |union, cell| union | self.candidates(*cell));
+                if union.count_ones() as usize != size {
+                    continue;
+                }
+                let eliminations: Vec<Candidate> = house
+                    .cells()
+                    .into_iter()
+                    .filter(|cell| !subset.contains(cell))
+                    .flat_map(|cell| {
+                        digits(self.candidates(cell) & union)
+                            .map(move |digit| Candidate::new(cell, digit))
+                    })
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique,
+                        cells: subset,
+                        houses: vec![house],
+                        placements: Vec::new(),
+                        eliminations,
+                        detail: StepDetail::None,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// When `size` digits of a house fit in only `size` cells between them, those cells
+    /// are used up by these digits and their other candidates can be removed.
+    pub(crate) fn find_hidden_subset(&self, size: usize) -> Option<Step> {
+        let technique = match size {
+            2 => Technique::HiddenPair,
+            3 => Technique::HiddenTriple,
+            _ => Technique::HiddenQuad,
+        };
+        for house in House::all() {
+            let open_digits: Vec<u8> = (1..=9)
+                .filter(|digit| {
+                    let count = self.cells_with_candidate(house, *digit).len();
+                    count >= 2 && count <= size
+                })
+                .collect();
+            for subset in combinations(&open_digits, size) {
+                let mut cells: Vec<usize> = subset
+                    .iter()
+                    .flat_map(|digit| self.cells_with_candidate(house, *digit))
+                    .collect();
+                cells.sort_unstable();
+                cells.dedup();
+                if cells.len() != size {
+                    continue;
+                }
+                let digit_mask = subset.iter().fold(0, |mask, digit| mask | 1 << (digit - 1));
+                let eliminations: Vec<Candidate> = cells
+                    .iter()
+                    .flat_map(|cell| {
+                        digits(self.candidates(*cell) & !digit_mask)
+                            .map(move |digit| Candidate::new(*cell, digit))
+                    })
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique,
+                        cells,
+                        houses: vec![house],
+                        placements: Vec::new(),
+                        eliminations,
+                        detail: StepDetail::None,
+                    });
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::logic::{cell_index, Candidate, House, Technique};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn find_naked_pair() {
+        // The first two cells of the top row can only hold 8 and 9, so no other cell in
+        // the row or the block can.
+        let sudoku = Sudoku::create_board(
+            "000123400\
+             000000000\
+             000000000\
+             567000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000"
+                .to_string(),
+        )
+        .unwrap();
+        let mut grid = sudoku.candidate_grid();
+        // Narrow the first two cells down by hand.
+        for digit in 5..=7 {
+            grid.eliminate(cell_index(0, 0), digit);
+            grid.eliminate(cell_index(0, 1), digit);
+        }
+        let step = grid.find_naked_subset(2).unwrap();
+        assert_eq!(step.technique, Technique::NakedPair);
+        assert_eq!(step.cells, vec![cell_index(0, 0), cell_index(0, 1)]);
+        assert_eq!(step.houses, vec![House::Row(0)]);
+        assert!(step
+            .eliminations
+            .contains(&Candidate::new(cell_index(0, 2), 8)));
+        assert!(step
+            .eliminations
+            .contains(&Candidate::new(cell_index(0, 8), 9)));
+    }
+
+    #[test]
+    fn find_hidden_pair() {
+        // In the top row, 1 and 2 only fit in the last two cells, since the other columns
+        // already have a 1 and a 2.
+        let sudoku = Sudoku::create_board(
+            "000000000\
+             120000000\
+             000120000\
+             000000000\
+             000000000\
+             000000000\
+             000000001\
+             000000002\
+             000000000"
+                .to_string(),
+        )
+        .unwrap();
+        let grid = sudoku.candidate_grid();
+        let step = grid.find_hidden_subset(2).unwrap();
+        assert_eq!(step.technique, Technique::HiddenPair);
+        assert_eq!(step.houses, vec![House::Row(0)]);
+        assert_eq!(step.cells, vec![cell_index(0, 6), cell_index(0, 7)]);
+        assert!(step
+            .eliminations
+            .iter()
+            .all(|elimination| elimination.digit > 2));
+    }
+}
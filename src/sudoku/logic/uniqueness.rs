@@ -0,0 +1,369 @@
+use crate::sudoku::logic::{
+    block_of, column_of, combinations, row_of, Candidate, CandidateGrid, House, Step, StepDetail,
+    Technique,
+};
+use crate::sudoku::{digits, ALL_VALUES};
+
+/// The corners of every rectangle whose two rows and two columns cover exactly two
+/// blocks, as top left, top right, bottom left, bottom right.
+fn rectangles() -> Vec<[usize; 4]> {
+    let mut rectangles = Vec::new();
+    for rows in combinations(&[0, 1, 2, 3, 4, 5, 6, 7, 8], 2) {
+        for columns in combinations(&[0, 1, 2, 3, 4, 5, 6, 7, 8], 2) {
+            let same_band = rows[0] / 3 == rows[1] / 3;
+            let same_stack = columns[0] / 3 == columns[1] / 3;
+            if same_band != same_stack {
+                rectangles.push([
+                    rows[0] * 9 + columns[0],
+                    rows[0] * 9 + columns[1],
+                    rows[1] * 9 + columns[0],
+                    rows[1] * 9 + columns[1],
+                ]);
+            }
+        }
+    }
+    rectangles
+}
+
+/// The houses that contain both cells.
+fn common_houses(first: usize, second: usize) -> Vec<House> {
+    let mut houses = Vec::new();
+    if row_of(first) == row_of(second) {
+        houses.push(House::Row(row_of(first)));
+    }
+    if column_of(first) == column_of(second) {
+        houses.push(House::Column(column_of(first)));
+    }
+    if block_of(first) == block_of(second) {
+        houses.push(House::Block(block_of(first)));
+    }
+    houses
+}
+
+impl CandidateGrid {
+    /// Look for a unique rectangle of the given type. Four corners that could all be
+    /// either of two digits form a deadly pattern: the two digits could be swapped and the
+    /// sudoku would have two solutions. In a sudoku with one solution at least one of the
+    /// corners must therefore hold another digit.
+    pub(crate) fn find_unique_rectangle(&self, kind: u8) -> Option<Step> {
+        for corners in rectangles() {
+            let shared = corners
+                .iter()
+                .fold(ALL_VALUES, |shared, cell| shared & self.candidates(*cell));
+            for pair in combinations(&digits(shared).collect::<Vec<u8>>(), 2) {
+                let mask = (1 << (pair[0] - 1)) | (1 << (pair[1] - 1));
+                let (floor, roof): (Vec<usize>, Vec<usize>) = corners
+                    .iter()
+                    .partition(|cell| self.candidates(**cell) == mask);
+                let found = match (kind, floor.len()) {
+                    (1, 3) => Some((
+                        Technique::UniqueRectangleType1,
+                        digits(mask).map(|d| Candidate::new(roof[0], d)).collect(),
+                        Vec::new(),
+                    )),
+                    (2..=4, 2) if !common_houses(roof[0], roof[1]).is_empty() => {
+                        self.unique_rectangle_roof(kind, mask, &roof)
+                    }
+                    _ => None,
+                };
+                if let Some((technique, eliminations, houses)) = found {
+                    return Some(Step {
+                        technique,
+                        cells: corners.to_vec(),
+                        houses,
+                        placements: Vec::new(),
+                        eliminations,
+                        detail: StepDetail::UniqueRectangle {
+                            corners: corners.to_vec(),
+                            digits: [pair[0], pair[1]],
+                        },
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// The eliminations of a unique rectangle of type 2, 3 or 4, where the two roof
+    /// corners next to each other have candidates besides the rectangle digits.
+    fn unique_rectangle_roof(
+        &self,
+        kind: u8,
+        mask: u16,
+        roof: &[usize],
+    ) -> Option<(Technique, Vec<Candidate>, Vec<House>)> {
+        let extras = [
+            self.candidates(roof[0]) & !mask,
+            self.candidates(roof[1]) & !mask,
+        ];
+        match kind {
+            // One of the roof corners holds the extra digit they share.
+            2 => {
+                if extras[0] != extras[1] || extras[0].count_ones() != 1 {
+                    return None;
+                }
+                let digit = digits(extras[0]).next().unwrap();
+                let eliminations = self.candidates_seeing_all(roof, digit);
+                (!eliminations.is_empty()).then_some((
+                    Technique::UniqueRectangleType2,
+                    eliminations,
+                    Vec::new(),
+                ))
+            }
+            // The extra digits act as one cell, which can form a naked subset with other
+            // cells of a house that holds both roof corners.
+            3 => {
+                let union = extras[0] | extras[1];
+                for house in common_houses(roof[0], roof[1]) {
+                    let others: Vec<usize> = house
+                        .cells()
+                        .into_iter()
+                        .filter(|cell| self.candidates(*cell) != 0 && !roof.contains(cell))
+                        .collect();
+                    for size in 1..=3 {
+                        for subset in combinations(&others, size) {
+                            let digits_used = subset
+                                .iter()
+                                .fold(union, |used, cell| used | self.candidates(*cell));
+                            if digits_used.count_ones() as usize != size + 1 {
+                                continue;
+                            }
+                            let eliminations: Vec<Candidate> = others
+                                .iter()
+                                .filter(|cell| !subset.contains(cell))
+                                .flat_map(|cell| {
+                                    digits(self.candidates(*cell) & digits_used)
+                                        .map(move |digit| Candidate::new(*cell, digit))
+                                })
+                                .collect();
+                            if !eliminations.is_empty() {
+                                return Some((
+                                    Technique::UniqueRectangleType3,
+                                    eliminations,
+                                    vec![house],
+                                ));
+                            }
+                        }
+                    }
+                }
+                None
+            }
+            // When a rectangle digit can only go in the roof corners of a house, one of
+            // them holds it and the other can not hold the other rectangle digit.
+            _ => {
+                for house in common_houses(roof[0], roof[1]) {
+                    for digit in digits(mask) {
+                        if self.cells_with_candidate(house, digit) != roof {
+                            continue;
+                        }
+                        let other = digits(mask).find(|d| *d != digit).unwrap();
+                        let eliminations: Vec<Candidate> = roof
+                            .iter()
+                            .map(|cell| Candidate::new(*cell, other))
+                            .collect();
+                        return Some((Technique::UniqueRectangleType4, eliminations, vec![house]));
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    /// When every empty cell has two candidates except one with three, and every digit
+    /// appears twice in each house, the grid would have two solutions without the third
+    /// candidate. The extra cell must therefore take the digit that appears three times in
+    /// its row, column and block.
+    pub(crate) fn find_bug_plus_one(&self) -> Option<Step> {
+        let open: Vec<usize> = (0..81).filter(|cell| self.candidates(*cell) != 0).collect();
+        let extra: Vec<usize> = open
+            .iter()
+            .copied()
+            .filter(|cell| self.candidates(*cell).count_ones() != 2)
+            .collect();
+        if extra.len() != 1 || self.candidates(extra[0]).count_ones() != 3 {
+            return None;
+        }
+        let cell = extra[0];
+        let houses = [
+            House::Row(row_of(cell)),
+            House::Column(column_of(cell)),
+            House::Block(block_of(cell)),
+        ];
+        let digit = digits(self.candidates(cell)).find(|digit| {
+            houses
+                .iter()
+                .all(|house| self.cells_with_candidate(*house, *digit).len() == 3)
+        })?;
+        // Without that third candidate every digit left in a house has to appear in
+        // exactly two of its cells, otherwise there is no deadly pattern to avoid.
+        let deadly = House::all().all(|house| {
+            (1..=9).all(|other| {
+                let count = self.cells_with_candidate(house, other).len();
+                let extra = other == digit && houses.contains(&house);
+                count == 0 || count == if extra { 3 } else { 2 }
+            })
+        });
+        if !deadly {
+            return None;
+        }
+        Some(Step {
+            technique: Technique::BugPlusOne,
+            cells: vec![cell],
+            houses: Vec::new(),
+            placements: vec![Candidate::new(cell, digit)],
+            eliminations: Vec::new(),
+            detail: StepDetail::None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::logic::test_support::grid_with;
+    use crate::sudoku::logic::{cell_index, Candidate, CandidateGrid, StepDetail, Technique};
+    use crate::sudoku::Sudoku;
+
+    /// The grid of [`grid_with`], assuming a unique solution.
+    fn unique_grid_with(cells: &[(usize, usize, &[u8])]) -> CandidateGrid {
+        let mut grid = grid_with(cells);
+        grid.assume_unique_solution();
+        grid
+    }
+
+    #[test]
+    fn find_unique_rectangle_type_1() {
+        let mut grid = Sudoku::create_board("0".repeat(81))
+            .unwrap()
+            .candidate_grid();
+        for (row, column) in [(0, 0), (0, 4), (1, 0)] {
+            for digit in 3..=9 {
+                grid.eliminate(cell_index(row, column), digit);
+            }
+        }
+        assert!(grid.find_step(Technique::UniqueRectangleType1).is_none());
+        grid.assume_unique_solution();
+        let step = grid.find_step(Technique::UniqueRectangleType1).unwrap();
+        assert_eq!(
+            step.eliminations,
+            vec![
+                Candidate::new(cell_index(1, 4), 1),
+                Candidate::new(cell_index(1, 4), 2)
+            ]
+        );
+        assert_eq!(
+            step.detail,
+            StepDetail::UniqueRectangle {
+                corners: vec![0, 4, 9, 13],
+                digits: [1, 2],
+            }
+        );
+    }
+
+    #[test]
+    fn find_unique_rectangle_type_2() {
+        let grid = unique_grid_with(&[
+            (0, 0, &[1, 2]),
+            (0, 4, &[1, 2]),
+            (1, 0, &[1, 2, 3]),
+            (1, 4, &[1, 2, 3]),
+        ]);
+        assert!(grid.find_unique_rectangle(1).is_none());
+        let step = grid.find_unique_rectangle(2).unwrap();
+        assert_eq!(step.technique, Technique::UniqueRectangleType2);
+        assert_eq!(step.eliminations.len(), 7);
+        assert!(step
+            .eliminations
+            .iter()
+            .all(|e| e.digit == 3 && e.cell / 9 == 1));
+    }
+
+    #[test]
+    fn find_unique_rectangle_type_3() {
+        let grid = unique_grid_with(&[
+            (0, 0, &[1, 2]),
+            (0, 4, &[1, 2]),
+            (1, 0, &[1, 2, 3]),
+            (1, 4, &[1, 2, 4]),
+            (1, 8, &[3, 4]),
+        ]);
+        let step = grid.find_unique_rectangle(3).unwrap();
+        assert_eq!(step.eliminations.len(), 12);
+        assert!(step
+            .eliminations
+            .iter()
+            .all(|e| [3, 4].contains(&e.digit) && e.cell / 9 == 1));
+    }
+
+    #[test]
+    fn find_unique_rectangle_type_4() {
+        let mut grid = unique_grid_with(&[
+            (0, 0, &[1, 2]),
+            (0, 4, &[1, 2]),
+            (1, 0, &[1, 2, 5]),
+            (1, 4, &[1, 2, 6]),
+        ]);
+        assert!(grid.find_unique_rectangle(4).is_none());
+        for column in [1, 2, 3, 5, 6, 7, 8] {
+            grid.eliminate(cell_index(1, column), 1);
+        }
+        let step = grid.find_unique_rectangle(4).unwrap();
+        assert_eq!(
+            step.eliminations,
+            vec![
+                Candidate::new(cell_index(1, 0), 2),
+                Candidate::new(cell_index(1, 4), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn find_bug_plus_one_in_sudoku() {
+        let sudoku = Sudoku::create_board(
+            "490002070\
+             302008000\
+             000070003\
+             000067008\
+             080030704\
+             030000000\
+             060009000\
+             000200035\
+             020080040"
+                .to_string(),
+        )
+        .unwrap();
+        let solution = sudoku.solve_logically();
+        assert!(solution.is_solved());
+        let step = solution
+            .steps
+            .iter()
+            .find(|step| step.technique == Technique::BugPlusOne)
+            .unwrap();
+        assert_eq!(step.placements, vec![Candidate::new(cell_index(2, 3), 1)]);
+        assert_eq!(solution.grid.value(cell_index(2, 3)), 1);
+
+        // Without the uniqueness techniques the BUG+1 is not used.
+        let steps = sudoku.candidate_grid().solve().steps;
+        assert!(steps
+            .iter()
+            .all(|step| !step.technique.requires_unique_solution()));
+    }
+
+    #[test]
+    fn no_bug_plus_one_without_deadly_pattern() {
+        // Every cell is bivalue except r1c1, and the 3 appears three times in its row,
+        // column and block, but the 4s and 5s fill whole houses.
+        let mut cells: Vec<(usize, usize, &[u8])> = Vec::new();
+        for row in 0..9 {
+            for column in 0..9 {
+                let keep: &[u8] = match (row, column) {
+                    (0, 0) => &[1, 2, 3],
+                    (0, 1) | (0, 2) | (3, 0) | (6, 0) => &[3, 4],
+                    _ => &[4, 5],
+                };
+                cells.push((row, column, keep));
+            }
+        }
+        let grid = unique_grid_with(&cells);
+        assert_eq!(grid.find_bug_plus_one(), None);
+    }
+}
@@ -0,0 +1,268 @@
+use crate::sudoku::digits;
+use crate::sudoku::logic::{
+    combinations, peers, sees, Candidate, CandidateGrid, House, Step, StepDetail, Technique,
+};
+
+fn wing_step(
+    technique: Technique,
+    pivot: Vec<usize>,
+    pincers: Vec<usize>,
+    digit: u8,
+    eliminations: Vec<Candidate>,
+) -> Step {
+    Step {
+        technique,
+        cells: pivot.iter().chain(pincers.iter()).copied().collect(),
+        houses: Vec::new(),
+        placements: Vec::new(),
+        eliminations,
+        detail: StepDetail::Wing {
+            pivot,
+            pincers,
+            digit,
+        },
+    }
+}
+
+impl CandidateGrid {
+    fn bivalue_cells(&self) -> Vec<usize> {
+        (0..81)
+            .filter(|cell| self.candidates(*cell).count_ones() == 2)
+            .collect()
+    }
+
+    /// A pivot with candidates xy that sees pincers with xz and yz: whichever value the
+    /// pivot takes, one of the pincers is z, so z can be removed from every cell that sees
+    /// both pincers.
+    pub(crate) fn find_xy_wing(&self) -> Option<Step> {
+        for pivot in self.bivalue_cells() {
+            let pivot_mask = self.candidates(pivot);
+            let pincers: Vec<usize> = peers(pivot)
+                .iter()
+                .copied()
+                .filter(|cell| {
+                    let mask = self.candidates(*cell);
+                    mask.count_ones() == 2 && (mask & pivot_mask).count_ones() == 1
+                })
+                .collect();
+            for pair in combinations(&pincers, 2) {
+                let (first, second) = (self.candidates(pair[0]), self.candidates(pair[1]));
+                let shared = first & second & !pivot_mask;
+                if shared == 0 || first & second & pivot_mask != 0 {
+                    continue;
+                }
+                let digit = digits(shared).next().unwrap();
+                let eliminations = self.candidates_seeing_all(&pair, digit);
+                if !eliminations.is_empty() {
+                    return Some(wing_step(
+                        Technique::XYWing,
+                        vec![pivot],
+                        pair,
+                        digit,
+                        eliminations,
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    /// A pivot with candidates xyz that sees pincers with xz and yz: one of the three
+    /// cells is z, so z can be removed from every cell that sees all three.
+    pub(crate) fn find_xyz_wing(&self) -> Option<Step> {
+        for pivot in (0..81).filter(|cell| self.candidates(*cell).count_ones() == 3) {
+            let pivot_mask = self.candidates(pivot);
+            let pincers: Vec<usize> = peers(pivot)
+                .iter()
+                .copied()
+                .filter(|cell| {
+                    let mask = self.candidates(*cell);
+                    mask.count_ones() == 2 && mask & pivot_mask == mask
+                })
+                .collect();
+            for pair in combinations(&pincers, 2) {
+                let (first, second) = (self.candidates(pair[0]), self.candidates(pair[1]));
+                if first == second {
+                    continue;
+                }
+                let digit = digits(first & second).next().unwrap();
+                let eliminations = self.candidates_seeing_all(&[pivot, pair[0], pair[1]], digit);
+                if !eliminations.is_empty() {
+                    return Some(wing_step(
+                        Technique::XYZWing,
+                        vec![pivot],
+                        pair,
+                        digit,
+                        eliminations,
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    /// Two cells with the same candidates xy that do not see each other, where one sees
+    /// one end and the other sees the other end of a strong link on x: at least one of the
+    /// two cells is y, so y can be removed from every cell that sees both.
+    pub(crate) fn find_w_wing(&self) -> Option<Step> {
+        let bivalue_cells = self.bivalue_cells();
+        for pair in combinations(&bivalue_cells, 2) {
+            let (first, second) = (pair[0], pair[1]);
+            let mask = self.candidates(first);
+            if mask != self.candidates(second) || sees(first, second) {
+                continue;
+            }
+            for link_digit in digits(mask) {
+                let digit = digits(mask & !(1 << (link_digit - 1))).next().unwrap();
+                for house in House::all() {
+                    let link = self.cells_with_candidate(house, link_digit);
+                    if link.len() != 2 || link.contains(&first) || link.contains(&second) {
+                        continue;
+                    }
+                    let joined = (sees(link[0], first) && sees(link[1], second))
+                        || (sees(link[1], first) && sees(link[0], second));
+                    if !joined {
+                        continue;
+                    }
+                    let eliminations = self.candidates_seeing_all(&pair, digit);
+                    if !eliminations.is_empty() {
+                        return Some(wing_step(Technique::WWing, link, pair, digit, eliminations));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// A pivot and three pincers it sees, with four digits between them. When every digit
+    /// but z only appears in cells that all see each other, those digits can fill at most
+    /// one cell each, so one of the four cells is z and z can be removed from every cell
+    /// that sees all the cells of the pattern holding z.
+    pub(crate) fn find_wxyz_wing(&self) -> Option<Step> {
+        let small = |cell: &usize| (2..=4).contains(&self.candidates(*cell).count_ones());
+        for pivot in (0..81).filter(small) {
+            let pincers: Vec<usize> = peers(pivot).iter().copied().filter(small).collect();
+            for trio in combinations(&pincers, 3) {
+                let cells = [pivot, trio[0], trio[1], trio[2]];
+                let union = cells
+                    .iter()
+                    .fold(0, |union, cell| union | self.candidates(*cell));
+                if union.count_ones() != 4 {
+                    continue;
+                }
+                let unrestricted: Vec<u8> = digits(union)
+                    .filter(|digit| {
+                        let holders: Vec<usize> = cells
+                            .iter()
+                            .copied()
+                            .filter(|cell| self.has_candidate(*cell, *digit))
+                            .collect();
+                        combinations(&holders, 2)
+                            .iter()
+                            .any(|pair| !sees(pair[0], pair[1]))
+                    })
+                    .collect();
+                if unrestricted.len() != 1 {
+                    continue;
+                }
+                let digit = unrestricted[0];
+                let holders: Vec<usize> = cells
+                    .iter()
+                    .copied()
+                    .filter(|cell| self.has_candidate(*cell, digit))
+                    .collect();
+                let eliminations = self.candidates_seeing_all(&holders, digit);
+                if !eliminations.is_empty() {
+                    return Some(wing_step(
+                        Technique::WXYZWing,
+                        vec![pivot],
+                        trio,
+                        digit,
+                        eliminations,
+                    ));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::logic::test_support::grid_with;
+    use crate::sudoku::logic::{cell_index, Candidate, StepDetail, Technique};
+
+    #[test]
+    fn find_xy_wing() {
+        let grid = grid_with(&[(0, 0, &[1, 2]), (0, 5, &[1, 3]), (4, 0, &[2, 3])]);
+        let step = grid.find_xy_wing().unwrap();
+        assert_eq!(step.technique, Technique::XYWing);
+        assert_eq!(
+            step.detail,
+            StepDetail::Wing {
+                pivot: vec![cell_index(0, 0)],
+                pincers: vec![cell_index(0, 5), cell_index(4, 0)],
+                digit: 3,
+            }
+        );
+        assert_eq!(step.eliminations, vec![Candidate::new(cell_index(4, 5), 3)]);
+        assert_eq!(
+            step.to_string(),
+            "XY-Wing with pivot r1c1 and pincers r1c6,r5c1: r5c6<>3"
+        );
+    }
+
+    #[test]
+    fn find_xyz_wing() {
+        let grid = grid_with(&[(0, 0, &[1, 2, 3]), (0, 6, &[1, 3]), (1, 1, &[2, 3])]);
+        assert!(grid.find_xy_wing().is_none());
+        let step = grid.find_xyz_wing().unwrap();
+        assert_eq!(
+            step.eliminations,
+            vec![
+                Candidate::new(cell_index(0, 1), 3),
+                Candidate::new(cell_index(0, 2), 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn find_w_wing() {
+        let mut grid = grid_with(&[(0, 0, &[1, 2]), (4, 4, &[1, 2])]);
+        for column in [1, 2, 3, 5, 6, 7, 8] {
+            grid.eliminate(cell_index(8, column), 1);
+        }
+        let step = grid.find_w_wing().unwrap();
+        assert_eq!(step.technique, Technique::WWing);
+        assert_eq!(
+            step.eliminations,
+            vec![
+                Candidate::new(cell_index(0, 4), 2),
+                Candidate::new(cell_index(4, 0), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn find_wxyz_wing() {
+        let grid = grid_with(&[
+            (0, 0, &[1, 2, 3]),
+            (0, 6, &[1, 4]),
+            (0, 7, &[2, 4]),
+            (1, 1, &[3, 4]),
+        ]);
+        let step = grid.find_wxyz_wing().unwrap();
+        let cells: Vec<usize> = step.eliminations.iter().map(|e| e.cell).collect();
+        assert_eq!(
+            cells,
+            vec![
+                cell_index(0, 1),
+                cell_index(0, 2),
+                cell_index(1, 6),
+                cell_index(1, 7),
+                cell_index(1, 8)
+            ]
+        );
+        assert!(step.eliminations.iter().all(|e| e.digit == 4));
+    }
+}
@@ -1,52 +1,229 @@
+use std::fmt;
 use std::fs::{read_to_string, File};
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader};
 
 pub mod checker;
+pub mod error;
+pub mod generator;
+pub mod logic;
+pub mod sat;
 pub mod solver;
+
+pub use error::SudokuError;
+pub use generator::{GeneratorOptions, Symmetry};
+
+/// Bit mask with a bit set for each of the digits 1 to 9, bit 0 standing for digit 1.
+pub(crate) const ALL_VALUES: u16 = 0b1_1111_1111;
+
+/// The digits whose bits are set in a mask, from low to high.
+fn digits(mask: u16) -> impl Iterator<Item = u8> {
+    (1..=9).filter(move |digit| mask & (1 << (digit - 1)) != 0)
+}
+
+/// A 9x9 sudoku board. Empty cells are shown as `'0'`, filled cells as the chars `'1'`
+/// to `'9'`.
+///
+/// Internally the digits are stored in a flat array, with `0` for an empty cell. For
+/// every row, column and block a bit mask records which digits it already contains,
+/// so the options for a cell are found without scanning its neighbours. The masks are
+/// updated whenever a value is placed or removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Sudoku {
-    board: Vec<Vec<char>>,
+    board: [u8; 81],
+    row_values: [u16; 9],
+    column_values: [u16; 9],
+    block_values: [u16; 9],
 }
 
 /// Read the first sudoku from a file that contains sudokus as a string of 81 chars on
-/// one line.
-pub fn read_single_sudoku_from_file(mut file_dir: String, file_name: &str) -> Result<String, ()> {
+/// one line. The file name is appended to the directory as is, so the directory needs
+/// a trailing separator.
+pub fn read_single_sudoku_from_file(
+    mut file_dir: String,
+    file_name: &str,
+) -> Result<String, SudokuError> {
     file_dir.push_str(file_name);
 
-    let sudoku_file = File::open(file_dir).unwrap();
+    let sudoku_file = File::open(&file_dir).map_err(|error| file_error(&file_dir, error))?;
     let buffer = BufReader::new(sudoku_file);
-    let sudoku_line = buffer.lines().next().unwrap().unwrap();
-    return Ok(sudoku_line);
+    match buffer.lines().next() {
+        Some(Ok(sudoku_line)) => Ok(sudoku_line),
+        Some(Err(error)) => Err(file_error(&file_dir, error)),
+        None => Err(SudokuError::EmptyFile(file_dir)),
+    }
 }
+
+/// Read every line of a file that contains sudokus as a string of 81 chars on one line.
 pub fn read_all_sudokus_from_file(
     mut file_dir: String,
     file_name: &str,
-) -> Result<Vec<String>, ()> {
+) -> Result<Vec<String>, SudokuError> {
     file_dir.push_str(file_name);
     let mut sudoku_lines: Vec<String> = Vec::new();
 
-    for line in read_to_string(file_dir).unwrap().lines() {
+    let contents = read_to_string(&file_dir).map_err(|error| file_error(&file_dir, error))?;
+    for line in contents.lines() {
         sudoku_lines.push(line.to_string());
     }
-    return Ok(sudoku_lines);
+    if sudoku_lines.is_empty() {
+        return Err(SudokuError::EmptyFile(file_dir));
+    }
+    Ok(sudoku_lines)
+}
+
+fn file_error(path: &str, error: io::Error) -> SudokuError {
+    match error.kind() {
+        io::ErrorKind::NotFound => SudokuError::FileNotFound(path.to_string()),
+        _ => SudokuError::UnreadableFile {
+            path: path.to_string(),
+            reason: error.to_string(),
+        },
+    }
 }
 
 impl Sudoku {
-    pub fn create_board(sudoku_line: String) -> Result<Self, ()> {
-        let mut board = Vec::new();
-        for (index, digit) in sudoku_line.chars().enumerate() {
-            if index % 9 == 0 {
-                board.push(Vec::new());
+    /// Create a board from a line of 81 cells, read row by row. Empty cells can be
+    /// written as `0`, `.`, `_` or `*`. Whitespace and the grid separators `|`, `-` and
+    /// `+` are ignored, so the output of [`Sudoku::to_grid`] is accepted as well.
+    /// Boards whose givens already conflict are rejected. Errors are reported for
+    /// line 1, see [`SudokuError::at_line`].
+    pub fn create_board(sudoku_line: String) -> Result<Self, SudokuError> {
+        let sudoku = Sudoku::parse_board(&sudoku_line)?;
+        if !sudoku.check_correctness_of_sudoku() {
+            return Err(SudokuError::ConflictingGivens { line: 1 });
+        }
+        Ok(sudoku)
+    }
+
+    /// Read the cells of a board without checking the givens against each other.
+    pub(crate) fn parse_board(sudoku_line: &str) -> Result<Self, SudokuError> {
+        let mut cells = Vec::new();
+        for (index, character) in sudoku_line.chars().enumerate() {
+            match character {
+                '1'..='9' => cells.push(character),
+                '0' | '.' | '_' | '*' => cells.push('0'),
+                '|' | '-' | '+' => {}
+                _ if character.is_whitespace() => {}
+                _ => {
+                    return Err(SudokuError::InvalidCharacter {
+                        line: 1,
+                        column: index + 1,
+                        character,
+                    })
+                }
             }
-            board[index / 9].push(digit);
         }
-        Ok(Sudoku { board })
+        if cells.len() != 81 {
+            return Err(SudokuError::InvalidLineLength {
+                line: 1,
+                length: cells.len(),
+            });
+        }
+        let mut sudoku = Sudoku {
+            board: [0; 81],
+            row_values: [0; 9],
+            column_values: [0; 9],
+            block_values: [0; 9],
+        };
+        for (index, cell) in cells.iter().enumerate() {
+            if *cell != '0' {
+                sudoku.place(index / 9, index % 9, *cell as u8 - b'0');
+            }
+        }
+        Ok(sudoku)
+    }
+
+    /// The index of the block a cell is in, counting left to right, top to bottom.
+    fn block_index(row: usize, column: usize) -> usize {
+        (row / 3) * 3 + column / 3
+    }
+
+    /// The digit in a cell, `0` if it is empty.
+    fn digit(&self, row: usize, column: usize) -> u8 {
+        self.board[row * 9 + column]
+    }
+
+    /// Fill a cell with a digit and mark the digit as used in the row, column and block.
+    fn place(&mut self, row: usize, column: usize, digit: u8) {
+        let bit = 1 << (digit - 1);
+        self.board[row * 9 + column] = digit;
+        self.row_values[row] |= bit;
+        self.column_values[column] |= bit;
+        self.block_values[Sudoku::block_index(row, column)] |= bit;
     }
 
+    /// Empty a cell and mark its digit as free again in the row, column and block.
+    fn unplace(&mut self, row: usize, column: usize) {
+        let digit = self.board[row * 9 + column];
+        if digit == 0 {
+            return;
+        }
+        let bit = !(1 << (digit - 1));
+        self.board[row * 9 + column] = 0;
+        self.row_values[row] &= bit;
+        self.column_values[column] &= bit;
+        self.block_values[Sudoku::block_index(row, column)] &= bit;
+    }
+
+    /// Bit mask of the digits that are not yet used in the row, column and block of a
+    /// cell.
+    fn candidates(&self, row: usize, column: usize) -> u16 {
+        let used = self.row_values[row]
+            | self.column_values[column]
+            | self.block_values[Sudoku::block_index(row, column)];
+        !used & ALL_VALUES
+    }
+
+    /// Create a board from rows of digits, where `0` marks an empty cell.
+    pub fn from_array(values: [[u8; 9]; 9]) -> Result<Self, SudokuError> {
+        let mut sudoku_line = String::new();
+        for (row, line) in values.iter().enumerate() {
+            for (column, value) in line.iter().enumerate() {
+                match char::from_digit(*value as u32, 10) {
+                    Some(digit) => sudoku_line.push(digit),
+                    None => {
+                        return Err(SudokuError::InvalidValue {
+                            row,
+                            column,
+                            value: *value,
+                        })
+                    }
+                }
+            }
+        }
+        Sudoku::create_board(sudoku_line)
+    }
+
+    /// The value of a single cell, `'0'` if it is empty.
+    pub fn value(&self, row: usize, column: usize) -> char {
+        (b'0' + self.digit(row, column)) as char
+    }
+
+    /// The board as rows of digits, where `0` marks an empty cell.
+    pub fn to_array(&self) -> [[u8; 9]; 9] {
+        let mut values = [[0; 9]; 9];
+        for (index, digit) in self.board.iter().enumerate() {
+            values[index / 9][index % 9] = *digit;
+        }
+        values
+    }
+
+    /// The board as a single line of 81 chars, the same format the readers consume.
+    pub fn to_line(&self) -> String {
+        self.board.iter().map(|digit| (b'0' + digit) as char).collect()
+    }
+
+    /// Print the board as a grid.
     pub fn display_sudoku_board(&self) {
+        println!("{}", self.to_grid());
+    }
+
+    /// The board as nine lines with separators between the blocks.
+    pub fn to_grid(&self) -> String {
         let mut board_string = String::new();
-        for (column_index, line) in self.board.iter().enumerate() {
+        for (column_index, line) in self.board.chunks(9).enumerate() {
             for (row_index, number) in line.iter().enumerate() {
-                board_string.push(number.clone());
+                board_string.push((b'0' + number) as char);
                 if row_index == 2 || row_index == 5 {
                     board_string.push('|');
                 }
@@ -58,6 +235,148 @@ impl Sudoku {
                 board_string.push_str("---+---+---\n");
             }
         }
-        println!("{board_string}");
+        board_string
+    }
+}
+
+impl fmt::Display for Sudoku {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_line())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::{read_all_sudokus_from_file, read_single_sudoku_from_file};
+    use crate::sudoku::{Sudoku, SudokuError, ALL_VALUES};
+
+    #[test]
+    fn convert_between_line_and_array() {
+        let sudoku_line = "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+        let sudoku = Sudoku::create_board(sudoku_line.to_string()).unwrap();
+        let values = sudoku.to_array();
+        assert_eq!(values[0], [5, 3, 0, 0, 7, 0, 0, 0, 0]);
+        assert_eq!(values[8], [0, 0, 0, 0, 8, 0, 0, 7, 9]);
+        assert_eq!(sudoku.value(1, 3), '1');
+        assert_eq!(Sudoku::from_array(values).unwrap(), sudoku);
+        assert_eq!(sudoku.to_line(), sudoku_line);
+        assert_eq!(sudoku.to_string(), sudoku_line);
+    }
+
+    #[test]
+    fn keep_value_masks_up_to_date() {
+        let mut sudoku = Sudoku::create_board("0".repeat(81)).unwrap();
+        sudoku.place(4, 5, 7);
+        assert_eq!(sudoku.value(4, 5), '7');
+        assert_eq!(sudoku.candidates(4, 0), ALL_VALUES & !(1 << 6));
+        assert_eq!(sudoku.candidates(0, 5), ALL_VALUES & !(1 << 6));
+        assert_eq!(sudoku.candidates(3, 3), ALL_VALUES & !(1 << 6));
+        assert_eq!(sudoku.candidates(0, 0), ALL_VALUES);
+        sudoku.unplace(4, 5);
+        assert_eq!(sudoku.value(4, 5), '0');
+        assert_eq!(sudoku.candidates(4, 0), ALL_VALUES);
+        assert_eq!(sudoku, Sudoku::create_board("0".repeat(81)).unwrap());
+    }
+
+    #[test]
+    fn reject_array_values_above_nine() {
+        let mut values = [[0; 9]; 9];
+        values[4][4] = 10;
+        assert_eq!(
+            Sudoku::from_array(values),
+            Err(SudokuError::InvalidValue {
+                row: 4,
+                column: 4,
+                value: 10
+            })
+        );
+    }
+
+    #[test]
+    fn reject_lines_of_the_wrong_length() {
+        assert_eq!(
+            Sudoku::create_board("0".repeat(70)),
+            Err(SudokuError::InvalidLineLength { line: 1, length: 70 })
+        );
+        assert_eq!(
+            Sudoku::create_board("0".repeat(100)).map_err(|error| error.at_line(3)),
+            Err(SudokuError::InvalidLineLength { line: 3, length: 100 })
+        );
+    }
+
+    #[test]
+    fn reject_invalid_characters() {
+        let mut sudoku_line = "0".repeat(81);
+        sudoku_line.replace_range(11..12, "x");
+        assert_eq!(
+            Sudoku::create_board(sudoku_line),
+            Err(SudokuError::InvalidCharacter {
+                line: 1,
+                column: 12,
+                character: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn accept_common_notations() {
+        let zeros = Sudoku::create_board(
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079"
+                .to_string(),
+        )
+        .unwrap();
+        let dots = Sudoku::create_board(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79"
+                .to_string(),
+        )
+        .unwrap();
+        let mixed = Sudoku::create_board(
+            "53_ _7_ *** | 6__ 195 ... | .98 ... .6.\n\
+             8.. .6. ..3 | 4.. 8.3 ..1 | 7.. .2. ..6\n\
+             .6. ... 28. | ... 419 ..5 | ... .8. .79"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(dots, zeros);
+        assert_eq!(mixed, zeros);
+        assert_eq!(Sudoku::create_board(zeros.to_grid()).unwrap(), zeros);
+    }
+
+    #[test]
+    fn reject_conflicting_givens() {
+        let mut sudoku_line = "0".repeat(81);
+        sudoku_line.replace_range(0..1, "5");
+        sudoku_line.replace_range(80..81, "5");
+        assert!(Sudoku::create_board(sudoku_line.clone()).is_ok());
+        sudoku_line.replace_range(72..73, "5");
+        assert_eq!(
+            Sudoku::create_board(sudoku_line).map_err(|error| error.at_line(7)),
+            Err(SudokuError::ConflictingGivens { line: 7 })
+        );
+    }
+
+    #[test]
+    fn report_missing_and_empty_files() {
+        let missing = "does/not/exist.txt";
+        assert_eq!(
+            read_single_sudoku_from_file(String::new(), missing),
+            Err(SudokuError::FileNotFound(missing.to_string()))
+        );
+        assert_eq!(
+            read_all_sudokus_from_file(String::new(), missing),
+            Err(SudokuError::FileNotFound(missing.to_string()))
+        );
+
+        let mut empty_file = std::env::temp_dir().to_string_lossy().to_string();
+        empty_file.push_str("/sudoku_solver_rust_empty.txt");
+        std::fs::write(&empty_file, "").unwrap();
+        assert_eq!(
+            read_single_sudoku_from_file(String::new(), &empty_file),
+            Err(SudokuError::EmptyFile(empty_file.clone()))
+        );
+        assert_eq!(
+            read_all_sudokus_from_file(String::new(), &empty_file),
+            Err(SudokuError::EmptyFile(empty_file.clone()))
+        );
     }
 }
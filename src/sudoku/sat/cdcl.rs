@@ -0,0 +1,416 @@
+use crate::sudoku::sat::Cnf;
+use crate::sudoku::solver::{SearchBudget, SearchFailure};
+
+/// The literal of a DIMACS number: variable `v` is `2 * (v - 1)`, its negation the next
+/// number, so negating a literal flips the lowest bit.
+fn literal(dimacs: i32) -> usize {
+    let index = (dimacs.unsigned_abs() as usize - 1) * 2;
+    if dimacs < 0 {
+        index + 1
+    } else {
+        index
+    }
+}
+
+fn variable_of(literal: usize) -> usize {
+    literal / 2
+}
+
+/// `1` when the literal is true, `-1` when it is false and `0` when its variable is not
+/// assigned yet.
+fn value(assignment: &[i8], literal: usize) -> i8 {
+    let value = assignment[variable_of(literal)];
+    if literal % 2 == 1 {
+        -value
+    } else {
+        value
+    }
+}
+
+/// A small conflict driven clause learning SAT solver.
+///
+/// It watches two literals of every clause for unit propagation, learns a clause at
+/// the first unique implication point of every conflict and jumps back to the level
+/// where that clause becomes unit. Decisions take the unassigned variable with the
+/// highest activity, which grows for the variables in recent conflicts, and give it
+/// the value it had last.
+///
+/// ```
+/// use sudoku_solver_rust::{CdclSolver, Cnf};
+///
+/// let cnf = Cnf {
+///     variables: 2,
+///     clauses: vec![vec![1, 2], vec![-1, 2], vec![-2, 1]],
+/// };
+/// let mut solver = CdclSolver::new(&cnf);
+/// assert_eq!(solver.solve(), Some(vec![1, 2]));
+/// solver.add_clause(&[-1, -2]);
+/// assert_eq!(solver.solve(), None);
+/// ```
+pub struct CdclSolver {
+    variables: usize,
+    clauses: Vec<Vec<usize>>,
+    /// For each literal the clauses that watch it, which are its first two literals.
+    watches: Vec<Vec<usize>>,
+    assignment: Vec<i8>,
+    level: Vec<usize>,
+    /// The clause that forced the value of each variable, `None` for decisions.
+    reason: Vec<Option<usize>>,
+    /// The true literals in the order they were assigned.
+    trail: Vec<usize>,
+    /// Where each decision level starts on the trail.
+    trail_limits: Vec<usize>,
+    propagated: usize,
+    activity: Vec<f64>,
+    increment: f64,
+    phase: Vec<bool>,
+    seen: Vec<bool>,
+    unsatisfiable: bool,
+}
+
+impl CdclSolver {
+    pub fn new(cnf: &Cnf) -> Self {
+        let mut solver = CdclSolver {
+            variables: cnf.variables,
+            clauses: Vec::with_capacity(cnf.clauses.len()),
+            watches: vec![Vec::new(); cnf.variables * 2],
+            assignment: vec![0; cnf.variables],
+            level: vec![0; cnf.variables],
+            reason: vec![None; cnf.variables],
+            trail: Vec::with_capacity(cnf.variables),
+            trail_limits: Vec::new(),
+            propagated: 0,
+            activity: vec![0.0; cnf.variables],
+            increment: 1.0,
+            phase: vec![false; cnf.variables],
+            seen: vec![false; cnf.variables],
+            unsatisfiable: false,
+        };
+        for clause in cnf.clauses.iter() {
+            solver.add_clause(clause);
+        }
+        solver
+    }
+
+    /// Add a clause of DIMACS literals. The search starts over from the top level, so
+    /// clauses can be added between calls to [`CdclSolver::solve`], for instance to rule
+    /// out a model that was found before. A `0` ends a clause in DIMACS and is skipped,
+    /// and a variable above the ones known so far is added to the formula.
+    pub fn add_clause(&mut self, clause: &[i32]) {
+        self.backtrack(0);
+        let mut literals: Vec<usize> = Vec::with_capacity(clause.len());
+        for number in clause.iter().filter(|number| **number != 0) {
+            self.grow_to(number.unsigned_abs() as usize);
+            let literal = literal(*number);
+            match value(&self.assignment, literal) {
+                1 => return,
+                -1 => {}
+                _ => {
+                    if literals.contains(&(literal ^ 1)) {
+                        return;
+                    }
+                    if !literals.contains(&literal) {
+                        literals.push(literal);
+                    }
+                }
+            }
+        }
+        match literals.len() {
+            0 => self.unsatisfiable = true,
+            1 => self.assign(literals[0], None),
+            _ => {
+                self.watch(self.clauses.len(), &literals);
+                self.clauses.push(literals);
+            }
+        }
+    }
+
+    /// Search for a model, returned as one DIMACS literal per variable, or `None` when the
+    /// clauses can not all be satisfied.
+    pub fn solve(&mut self) -> Option<Vec<i32>> {
+        self.solve_with_budget(&mut SearchBudget::unlimited()).ok()
+    }
+
+    /// Search for a model like [`CdclSolver::solve`], spending the budget on decisions.
+    pub(crate) fn solve_with_budget(
+        &mut self,
+        budget: &mut SearchBudget,
+    ) -> Result<Vec<i32>, SearchFailure> {
+        if self.unsatisfiable {
+            return Err(SearchFailure::Exhausted);
+        }
+        self.backtrack(0);
+        loop {
+            if let Some(conflict) = self.propagate() {
+                if self.trail_limits.is_empty() {
+                    self.unsatisfiable = true;
+                    return Err(SearchFailure::Exhausted);
+                }
+                let (learnt, level) = self.analyze(conflict);
+                self.backtrack(level);
+                self.learn(learnt);
+                self.increment /= 0.95;
+                continue;
+            }
+            match self.pick_branch() {
+                Some(literal) => {
+                    budget.spend()?;
+                    self.trail_limits.push(self.trail.len());
+                    self.assign(literal, None);
+                }
+                None => {
+                    return Ok((0..self.variables)
+                        .map(|variable| {
+                            let number = variable as i32 + 1;
+                            if self.assignment[variable] == 1 {
+                                number
+                            } else {
+                                -number
+                            }
+                        })
+                        .collect())
+                }
+            }
+        }
+    }
+
+    /// Make room for the variables up to `variables`, unassigned and without activity.
+    fn grow_to(&mut self, variables: usize) {
+        if variables <= self.variables {
+            return;
+        }
+        self.variables = variables;
+        self.watches.resize(variables * 2, Vec::new());
+        self.assignment.resize(variables, 0);
+        self.level.resize(variables, 0);
+        self.reason.resize(variables, None);
+        self.activity.resize(variables, 0.0);
+        self.phase.resize(variables, false);
+        self.seen.resize(variables, false);
+    }
+
+    fn watch(&mut self, clause: usize, literals: &[usize]) {
+        self.watches[literals[0]].push(clause);
+        self.watches[literals[1]].push(clause);
+    }
+
+    fn assign(&mut self, literal: usize, reason: Option<usize>) {
+        let variable = variable_of(literal);
+        self.assignment[variable] = if literal % 2 == 1 { -1 } else { 1 };
+        self.level[variable] = self.trail_limits.len();
+        self.reason[variable] = reason;
+        self.trail.push(literal);
+    }
+
+    /// Undo every assignment above the level, remembering the values as phases.
+    fn backtrack(&mut self, level: usize) {
+        if self.trail_limits.len() <= level {
+            return;
+        }
+        let limit = self.trail_limits[level];
+        for literal in self.trail.drain(limit..) {
+            let variable = variable_of(literal);
+            self.phase[variable] = literal % 2 == 0;
+            self.assignment[variable] = 0;
+            self.reason[variable] = None;
+        }
+        self.trail_limits.truncate(level);
+        self.propagated = limit;
+    }
+
+    /// Assign the literals that clauses force, until nothing more follows or a clause
+    /// has all its literals false. Returns that clause.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let false_literal = self.trail[self.propagated] ^ 1;
+            self.propagated += 1;
+            let mut watchers = std::mem::take(&mut self.watches[false_literal]);
+            let mut index = 0;
+            while index < watchers.len() {
+                let clause_index = watchers[index];
+                let clause = &mut self.clauses[clause_index];
+                if clause[0] == false_literal {
+                    clause.swap(0, 1);
+                }
+                if value(&self.assignment, clause[0]) == 1 {
+                    index += 1;
+                    continue;
+                }
+                let replacement =
+                    (2..clause.len()).find(|k| value(&self.assignment, clause[*k]) != -1);
+                if let Some(k) = replacement {
+                    clause.swap(1, k);
+                    self.watches[clause[1]].push(clause_index);
+                    watchers.swap_remove(index);
+                    continue;
+                }
+                let first = clause[0];
+                if value(&self.assignment, first) == -1 {
+                    self.watches[false_literal] = watchers;
+                    self.propagated = self.trail.len();
+                    return Some(clause_index);
+                }
+                self.assign(first, Some(clause_index));
+                index += 1;
+            }
+            self.watches[false_literal] = watchers;
+        }
+        None
+    }
+
+    /// Learn from a conflict: walk back along the trail of the current level, resolving
+    /// with the reasons, until one literal of the level is left. Returns the learnt
+    /// clause with that literal, negated, first, and the level to jump back to.
+    fn analyze(&mut self, conflict: usize) -> (Vec<usize>, usize) {
+        let current = self.trail_limits.len();
+        let mut learnt = vec![0];
+        let mut pending = 0;
+        let mut clause = conflict;
+        let mut resolved = None;
+        let mut index = self.trail.len();
+        loop {
+            for position in 0..self.clauses[clause].len() {
+                let literal = self.clauses[clause][position];
+                let variable = variable_of(literal);
+                if Some(variable) == resolved || self.seen[variable] || self.level[variable] == 0 {
+                    continue;
+                }
+                self.seen[variable] = true;
+                self.bump(variable);
+                if self.level[variable] == current {
+                    pending += 1;
+                } else {
+                    learnt.push(literal);
+                }
+            }
+            loop {
+                index -= 1;
+                if self.seen[variable_of(self.trail[index])] {
+                    break;
+                }
+            }
+            let literal = self.trail[index];
+            let variable = variable_of(literal);
+            self.seen[variable] = false;
+            pending -= 1;
+            if pending == 0 {
+                learnt[0] = literal ^ 1;
+                break;
+            }
+            clause = self.reason[variable].expect("implied literals have a reason");
+            resolved = Some(variable);
+        }
+        let mut level = 0;
+        for position in 1..learnt.len() {
+            let variable = variable_of(learnt[position]);
+            self.seen[variable] = false;
+            if self.level[variable] > level {
+                level = self.level[variable];
+                learnt.swap(1, position);
+            }
+        }
+        (learnt, level)
+    }
+
+    /// Add a learnt clause after jumping back and assign the literal it forces.
+    fn learn(&mut self, learnt: Vec<usize>) {
+        let literal = learnt[0];
+        if learnt.len() == 1 {
+            self.assign(literal, None);
+            return;
+        }
+        let clause = self.clauses.len();
+        self.watch(clause, &learnt);
+        self.clauses.push(learnt);
+        self.assign(literal, Some(clause));
+    }
+
+    fn bump(&mut self, variable: usize) {
+        self.activity[variable] += self.increment;
+        if self.activity[variable] > 1e100 {
+            for activity in self.activity.iter_mut() {
+                *activity *= 1e-100;
+            }
+            self.increment *= 1e-100;
+        }
+    }
+
+    /// The most active unassigned variable with its saved phase, `None` when all
+    /// variables are assigned.
+    fn pick_branch(&self) -> Option<usize> {
+        let variable = (0..self.variables)
+            .filter(|variable| self.assignment[*variable] == 0)
+            .max_by(|a, b| self.activity[*a].total_cmp(&self.activity[*b]))?;
+        Some(variable * 2 + usize::from(!self.phase[variable]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::sat::{CdclSolver, Cnf};
+
+    /// Whether every clause has a literal in the model.
+    fn satisfies(cnf: &Cnf, model: &[i32]) -> bool {
+        cnf.clauses
+            .iter()
+            .all(|clause| clause.iter().any(|literal| model.contains(literal)))
+    }
+
+    #[test]
+    fn solve_small_formulas() {
+        let cnf = Cnf {
+            variables: 3,
+            clauses: vec![vec![1, 2], vec![-1, 3], vec![-2, 3], vec![-3, -1]],
+        };
+        let model = CdclSolver::new(&cnf).solve().unwrap();
+        assert!(satisfies(&cnf, &model));
+        assert_eq!(model, vec![-1, 2, 3]);
+
+        let contradiction = Cnf {
+            variables: 1,
+            clauses: vec![vec![1], vec![-1]],
+        };
+        assert_eq!(CdclSolver::new(&contradiction).solve(), None);
+    }
+
+    #[test]
+    fn prove_pigeonhole_unsatisfiable() {
+        // Four pigeons in three holes: variable 3 * pigeon + hole + 1.
+        let mut cnf = Cnf {
+            variables: 12,
+            clauses: Vec::new(),
+        };
+        for pigeon in 0..4 {
+            cnf.clauses
+                .push((0..3).map(|hole| 3 * pigeon + hole + 1).collect());
+        }
+        for hole in 0..3 {
+            for first in 0..4 {
+                for second in first + 1..4 {
+                    cnf.clauses
+                        .push(vec![-(3 * first + hole + 1), -(3 * second + hole + 1)]);
+                }
+            }
+        }
+        assert_eq!(CdclSolver::new(&cnf).solve(), None);
+        // Without the last pigeon everyone fits.
+        cnf.clauses.remove(3);
+        let model = CdclSolver::new(&cnf).solve().unwrap();
+        assert!(satisfies(&cnf, &model));
+    }
+
+    #[test]
+    fn add_variables_beyond_the_declared_ones() {
+        let cnf = Cnf {
+            variables: 1,
+            clauses: vec![vec![1, 0], vec![-1, 5, 0]],
+        };
+        let mut solver = CdclSolver::new(&cnf);
+        let model = solver.solve().unwrap();
+        assert_eq!(model.len(), 5);
+        assert!(model.contains(&1) && model.contains(&5));
+        solver.add_clause(&[-7]);
+        solver.add_clause(&[-5, 7]);
+        assert_eq!(solver.solve(), None);
+    }
+}
@@ -0,0 +1,274 @@
+//! Solving a sudoku as a boolean satisfiability problem: encode it as a formula in
+//! conjunctive normal form, write that in the DIMACS format any SAT solver reads, and
+//! read the model a solver prints back into a board. [`CdclSolver`] solves the formula
+//! without leaving the crate.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use crate::sudoku::solver::{SearchBudget, SearchFailure};
+use crate::sudoku::{Sudoku, SudokuError};
+
+mod cdcl;
+
+pub use cdcl::CdclSolver;
+
+/// A formula in conjunctive normal form: every clause needs one of its literals to be
+/// true. Literals are DIMACS numbers, `v` for variable `v` and `-v` for its negation,
+/// with variables counted from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cnf {
+    pub variables: usize,
+    pub clauses: Vec<Vec<i32>>,
+}
+
+/// The variable that is true when the cell holds the digit, from 1 for a 1 in the top
+/// left corner to 729 for a 9 in the bottom right one.
+pub fn variable(row: usize, column: usize, digit: u8) -> i32 {
+    (row * 81 + column * 9 + digit as usize) as i32
+}
+
+/// The cells of each row, column and block, as `(row, column)`.
+fn units() -> Vec<[(usize, usize); 9]> {
+    let mut units = Vec::with_capacity(27);
+    for index in 0..9 {
+        units.push(std::array::from_fn(|i| (index, i)));
+        units.push(std::array::from_fn(|i| (i, index)));
+        units.push(std::array::from_fn(|i| {
+            ((index / 3) * 3 + i / 3, (index % 3) * 3 + i % 3)
+        }));
+    }
+    units
+}
+
+impl Sudoku {
+    /// Encode the board as a formula with a variable for each digit in each cell, see
+    /// [`variable`]. Every cell holds exactly one digit, every row, column and block holds
+    /// every digit exactly once, and each given is a clause of its own.
+    pub fn to_cnf(&self) -> Cnf {
+        let mut clauses = Vec::new();
+        for row in 0..9 {
+            for column in 0..9 {
+                clauses.push((1..=9).map(|digit| variable(row, column, digit)).collect());
+                for first in 1..=9 {
+                    for second in first + 1..=9 {
+                        clauses.push(vec![
+                            -variable(row, column, first),
+                            -variable(row, column, second),
+                        ]);
+                    }
+                }
+            }
+        }
+        for unit in units() {
+            for digit in 1..=9 {
+                clauses.push(unit.iter().map(|(r, c)| variable(*r, *c, digit)).collect());
+                for (index, (row, column)) in unit.iter().enumerate() {
+                    for (other_row, other_column) in unit[index + 1..].iter() {
+                        clauses.push(vec![
+                            -variable(*row, *column, digit),
+                            -variable(*other_row, *other_column, digit),
+                        ]);
+                    }
+                }
+            }
+        }
+        for (cell, digit) in self.board.iter().enumerate() {
+            if *digit != 0 {
+                clauses.push(vec![variable(cell / 9, cell % 9, *digit)]);
+            }
+        }
+        Cnf {
+            variables: 729,
+            clauses,
+        }
+    }
+
+    /// Read a board back from a model of the formula of [`Sudoku::to_cnf`], as returned by
+    /// [`parse_dimacs_model`] or [`CdclSolver::solve`]. Every cell needs exactly one
+    /// true variable.
+    pub fn from_model(model: &[i32]) -> Result<Sudoku, SudokuError> {
+        let mut values = [[0; 9]; 9];
+        for literal in model.iter().filter(|literal| **literal > 0) {
+            if *literal > 729 {
+                return Err(SudokuError::InvalidModel(format!(
+                    "variable {literal} is not a digit in a cell"
+                )));
+            }
+            let index = *literal as usize - 1;
+            let (row, column, digit) = (index / 81, index / 9 % 9, (index % 9) as u8 + 1);
+            if values[row][column] != 0 {
+                return Err(SudokuError::InvalidModel(format!(
+                    "cell ({row}, {column}) holds both {} and {digit}",
+                    values[row][column]
+                )));
+            }
+            values[row][column] = digit;
+        }
+        if let Some(cell) = values.as_flattened().iter().position(|value| *value == 0) {
+            return Err(SudokuError::InvalidModel(format!(
+                "cell ({}, {}) holds no digit",
+                cell / 9,
+                cell % 9
+            )));
+        }
+        Sudoku::from_array(values)
+    }
+
+    pub(crate) fn solve_with_sat(
+        &mut self,
+        budget: &mut SearchBudget,
+    ) -> Result<(), SearchFailure> {
+        let model = CdclSolver::new(&self.to_cnf()).solve_with_budget(budget)?;
+        *self = Sudoku::from_model(&model).map_err(|_| SearchFailure::Exhausted)?;
+        Ok(())
+    }
+}
+
+impl Cnf {
+    /// The formula in the DIMACS format: a `p cnf` header with the number of variables
+    /// and clauses, then one clause per line ending in `0`.
+    pub fn to_dimacs(&self) -> String {
+        let mut dimacs = format!("p cnf {} {}\n", self.variables, self.clauses.len());
+        for clause in self.clauses.iter() {
+            for literal in clause.iter() {
+                write!(dimacs, "{literal} ").unwrap();
+            }
+            dimacs.push_str("0\n");
+        }
+        dimacs
+    }
+
+    /// Write the formula in the DIMACS format, see [`Cnf::to_dimacs`].
+    pub fn write_dimacs<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(self.to_dimacs().as_bytes())
+    }
+}
+
+/// Read the output of a SAT solver in the DIMACS model format: an `s SATISFIABLE` or
+/// `s UNSATISFIABLE` line and `v` lines with the literals of the model, ended by `0`.
+/// The plain `SAT` and `UNSAT` lines and literal lines without the `v` of MiniSat are
+/// accepted as well, and `c` comment lines are skipped. Returns `None` for an
+/// unsatisfiable formula.
+pub fn parse_dimacs_model(output: &str) -> Result<Option<Vec<i32>>, SudokuError> {
+    let mut satisfiable = None;
+    let mut model = Vec::new();
+    for line in output.lines().map(str::trim) {
+        let mut words = line.split_whitespace();
+        let literals = match words.next() {
+            None | Some("c") => continue,
+            Some("s") => {
+                satisfiable = Some(words.next() == Some("SATISFIABLE"));
+                continue;
+            }
+            Some("SAT") | Some("SATISFIABLE") => {
+                satisfiable = Some(true);
+                continue;
+            }
+            Some("UNSAT") | Some("UNSATISFIABLE") => {
+                satisfiable = Some(false);
+                continue;
+            }
+            Some("v") => words,
+            Some(_) => line.split_whitespace(),
+        };
+        for word in literals {
+            let literal: i32 = word
+                .parse()
+                .map_err(|_| SudokuError::InvalidModel(format!("'{word}' is not a literal")))?;
+            if literal != 0 {
+                model.push(literal);
+            }
+        }
+    }
+    match satisfiable {
+        Some(false) => Ok(None),
+        _ if model.is_empty() => Err(SudokuError::InvalidModel(
+            "the output contains no model".to_string(),
+        )),
+        _ => Ok(Some(model)),
+    }
+}
+
+/// The solutions of a sudoku one after another: after each model the formula gets a
+/// clause that rules out the digits it put in the empty cells.
+pub(crate) struct SatSolutions {
+    solver: CdclSolver,
+    empty_cells: Vec<usize>,
+}
+
+impl SatSolutions {
+    pub(crate) fn new(sudoku: &Sudoku) -> Self {
+        SatSolutions {
+            solver: CdclSolver::new(&sudoku.to_cnf()),
+            empty_cells: (0..81).filter(|cell| sudoku.board[*cell] == 0).collect(),
+        }
+    }
+
+    pub(crate) fn next_solution(
+        &mut self,
+        budget: &mut SearchBudget,
+    ) -> Result<Sudoku, SearchFailure> {
+        let model = self.solver.solve_with_budget(budget)?;
+        let solution = Sudoku::from_model(&model).map_err(|_| SearchFailure::Exhausted)?;
+        let blocking: Vec<i32> = self
+            .empty_cells
+            .iter()
+            .map(|cell| -variable(cell / 9, cell % 9, solution.board[*cell]))
+            .collect();
+        self.solver.add_clause(&blocking);
+        Ok(solution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::sat::{parse_dimacs_model, variable, CdclSolver};
+    use crate::sudoku::{Sudoku, SudokuError};
+
+    const PUZZLE: &str = "530070000\
+                          600195000\
+                          098000060\
+                          800060003\
+                          400803001\
+                          700020006\
+                          060000280\
+                          000419005\
+                          000080079";
+
+    #[test]
+    fn encode_as_dimacs() {
+        let sudoku = Sudoku::create_board(PUZZLE.to_string()).unwrap();
+        let cnf = sudoku.to_cnf();
+        assert_eq!(cnf.variables, 729);
+        // 4 * 81 clauses for "at least one" and 4 * 81 * 36 for "at most one".
+        assert_eq!(cnf.clauses.len(), 4 * 81 * 37 + 30);
+        assert!(cnf.clauses.contains(&vec![variable(0, 0, 5)]));
+        let dimacs = cnf.to_dimacs();
+        assert!(dimacs.starts_with(&format!("p cnf 729 {}\n", cnf.clauses.len())));
+        assert_eq!(dimacs.lines().count(), cnf.clauses.len() + 1);
+        let mut written = Vec::new();
+        cnf.write_dimacs(&mut written).unwrap();
+        assert_eq!(written, dimacs.into_bytes());
+    }
+
+    #[test]
+    fn solve_through_the_pipeline() {
+        let sudoku = Sudoku::create_board(PUZZLE.to_string()).unwrap();
+        let model = CdclSolver::new(&sudoku.to_cnf()).solve().unwrap();
+        let literals: Vec<String> = model.iter().map(|literal| literal.to_string()).collect();
+        let output = format!("c solved\ns SATISFIABLE\nv {} 0\n", literals.join(" "));
+        let parsed = parse_dimacs_model(&output).unwrap().unwrap();
+        let solution = Sudoku::from_model(&parsed).unwrap();
+        assert!(solution.check_sudoku_completed());
+        assert_eq!(solution.value(0, 2), '4');
+
+        assert_eq!(parse_dimacs_model("s UNSATISFIABLE\n"), Ok(None));
+        assert_eq!(parse_dimacs_model("SAT\n1 -2 0\n"), Ok(Some(vec![1, -2])));
+        assert!(parse_dimacs_model("s SATISFIABLE\nv 1 x 0\n").is_err());
+        assert!(matches!(
+            Sudoku::from_model(&[1, 2]),
+            Err(SudokuError::InvalidModel(_))
+        ));
+    }
+}
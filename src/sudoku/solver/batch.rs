@@ -0,0 +1,150 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::sudoku::solver::{SolveOutcome, SolverAlgorithm};
+use crate::sudoku::Sudoku;
+
+/// How [`solve_batch`] spreads the sudokus over threads and solves them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchOptions {
+    pub algorithm: SolverAlgorithm,
+    /// The number of worker threads. `0` is taken as `1`.
+    pub threads: usize,
+    /// Give up on a sudoku after visiting this many nodes of the search tree.
+    pub node_limit: Option<u64>,
+}
+
+impl Default for BatchOptions {
+    /// The hash map solver, without a node limit, on one thread per available core.
+    fn default() -> Self {
+        BatchOptions {
+            algorithm: SolverAlgorithm::EmptyCellHashMap,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            node_limit: None,
+        }
+    }
+}
+
+/// How one sudoku of a batch was solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchResult {
+    /// The position of the sudoku in the input.
+    pub index: usize,
+    pub algorithm: SolverAlgorithm,
+    pub outcome: SolveOutcome,
+    /// The solution, or the sudoku as given when it was not solved.
+    pub sudoku: Sudoku,
+}
+
+fn solve_one(index: usize, sudoku: &Sudoku, options: &BatchOptions) -> BatchResult {
+    let mut sudoku = sudoku.clone();
+    let outcome = match options.node_limit {
+        Some(node_limit) => sudoku.solve_sudoku_with_node_limit(&options.algorithm, node_limit),
+        None => sudoku.solve_sudoku(&options.algorithm),
+    };
+    BatchResult {
+        index,
+        algorithm: options.algorithm,
+        outcome,
+        sudoku,
+    }
+}
+
+/// Solve every sudoku on a pool of worker threads. Each worker takes the next sudoku
+/// nobody has started on yet, so slow sudokus do not hold up the others. The results
+/// come back in the order of the input, whatever order they were solved in.
+pub fn solve_batch(sudokus: &[Sudoku], options: &BatchOptions) -> Vec<BatchResult> {
+    let workers = options.threads.clamp(1, sudokus.len().max(1));
+    if workers == 1 {
+        return sudokus
+            .iter()
+            .enumerate()
+            .map(|(index, sudoku)| solve_one(index, sudoku, options))
+            .collect();
+    }
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<BatchResult>> = vec![None; sudokus.len()];
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut solved = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        match sudokus.get(index) {
+                            Some(sudoku) => solved.push(solve_one(index, sudoku, options)),
+                            None => return solved,
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            for result in handle.join().expect("a batch worker panicked") {
+                let index = result.index;
+                results[index] = Some(result);
+            }
+        }
+    });
+    results
+        .into_iter()
+        .map(|result| result.expect("every sudoku is solved by a worker"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::solver::batch::{solve_batch, BatchOptions};
+    use crate::sudoku::solver::{SolveOutcome, SolverAlgorithm};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn solve_in_input_order_on_several_threads() {
+        let mut sudokus = vec![
+            Sudoku::create_board(
+                "012345678\
+                 900000000\
+                 000000000\
+                 000000000\
+                 000000000\
+                 000000000\
+                 000000000\
+                 000000000\
+                 000000000"
+                    .to_string(),
+            )
+            .unwrap(),
+            Sudoku::create_board("0".repeat(81)).unwrap(),
+        ];
+        for line in [
+            "530070000600195000098000060800060003400803001700020006060000280000419005000080079",
+            "800000000003600000070090200050007000000045700000100030001000068008500010090000400",
+            "000000405040090008060700300000960007008403100000001000005000002081600000902000600",
+        ] {
+            sudokus.push(Sudoku::create_board(line.to_string()).unwrap());
+        }
+        let options = BatchOptions {
+            algorithm: SolverAlgorithm::MostRestricted,
+            threads: 3,
+            node_limit: None,
+        };
+        let results = solve_batch(&sudokus, &options);
+        assert_eq!(results.len(), sudokus.len());
+        assert_eq!(results[0].outcome, SolveOutcome::Unsolvable);
+        assert_eq!(results[0].sudoku, sudokus[0]);
+        for (index, result) in results.iter().enumerate() {
+            assert_eq!(result.index, index);
+            assert_eq!(result.algorithm, SolverAlgorithm::MostRestricted);
+            let mut sudoku = sudokus[index].clone();
+            assert_eq!(result.outcome, sudoku.solve_sudoku(&options.algorithm));
+            assert_eq!(result.sudoku, sudoku);
+        }
+
+        let single = BatchOptions {
+            threads: 0,
+            ..options
+        };
+        assert_eq!(solve_batch(&sudokus, &single), results);
+        assert!(solve_batch(&[], &options).is_empty());
+    }
+}
@@ -0,0 +1,305 @@
+use crate::sudoku::solver::{SearchBudget, SearchFailure};
+use crate::sudoku::Sudoku;
+
+/// The nine cells of each row of a band.
+const ROWS: [u32; 3] = [0x1ff, 0x1ff << 9, 0x1ff << 18];
+/// The cells of a block in a band, for the three blocks from left to right.
+const BLOCKS: [u32; 3] = [0x1c0e07, 0x1c0e07 << 3, 0x1c0e07 << 6];
+/// The three cells of a column in a band.
+const COLUMN: u32 = 1 | (1 << 9) | (1 << 18);
+const BAND: u32 = (1 << 27) - 1;
+
+/// A board as bitboards over the three bands of three rows. For every digit and band a
+/// 27 bit mask holds the cells the digit can still go in, row by row; a placed digit
+/// keeps the bit of its own cell. The masks of all digits lie next to each other, so an
+/// update touches a few cache lines at most, and the whole board is copied on a guess
+/// instead of undone.
+#[derive(Clone, Copy)]
+struct Board {
+    candidates: [[u32; 3]; 9],
+    /// The cells that do not hold a value yet.
+    unsolved: [u32; 3],
+}
+
+impl Board {
+    fn new() -> Self {
+        Board {
+            candidates: [[BAND; 3]; 9],
+            unsolved: [BAND; 3],
+        }
+    }
+
+    fn from_sudoku(sudoku: &Sudoku) -> Self {
+        let mut board = Board::new();
+        for (cell, digit) in sudoku.board.iter().enumerate() {
+            if *digit != 0 {
+                board.place(*digit as usize - 1, cell / 27, cell % 27);
+            }
+        }
+        board
+    }
+
+    /// Put the digit, counted from 0, at a position in a band: no other digit fits the
+    /// cell any more, and the digit fits no other cell of its row, column and block.
+    fn place(&mut self, digit: usize, band: usize, position: usize) {
+        let bit = 1 << position;
+        for masks in self.candidates.iter_mut() {
+            masks[band] &= !bit;
+        }
+        let column = COLUMN << (position % 9);
+        let masks = &mut self.candidates[digit];
+        for mask in masks.iter_mut() {
+            *mask &= !column;
+        }
+        masks[band] &= !(ROWS[position / 9] | BLOCKS[position % 9 / 3]);
+        masks[band] |= bit;
+        self.unsolved[band] &= !bit;
+    }
+
+    fn is_solved(&self) -> bool {
+        self.unsolved == [0; 3]
+    }
+
+    /// The digit whose mask has the bit, if any is left.
+    fn digit_at(&self, band: usize, bit: u32) -> Option<usize> {
+        (0..9).find(|digit| self.candidates[*digit][band] & bit != 0)
+    }
+
+    /// Place naked and hidden singles until none are left. Returns `false` when a cell
+    /// is left without a digit or a row, column or block without a place for one.
+    fn propagate(&mut self) -> bool {
+        'search: loop {
+            let mut placed = false;
+            for band in 0..3 {
+                let (mut once, mut twice) = (0, 0);
+                for masks in self.candidates.iter() {
+                    twice |= once & masks[band];
+                    once |= masks[band];
+                }
+                if self.unsolved[band] & !once != 0 {
+                    return false;
+                }
+                let mut singles = self.unsolved[band] & once & !twice;
+                while singles != 0 {
+                    let position = singles.trailing_zeros() as usize;
+                    singles &= singles - 1;
+                    match self.digit_at(band, 1 << position) {
+                        Some(digit) => self.place(digit, band, position),
+                        None => return false,
+                    }
+                    placed = true;
+                }
+            }
+            for digit in 0..9 {
+                let masks = self.candidates[digit];
+                let (mut once, mut twice) = (0, 0);
+                for (band, mask) in masks.iter().enumerate() {
+                    let unsolved = self.unsolved[band];
+                    for row in ROWS.iter().chain(BLOCKS.iter()) {
+                        let places = mask & row;
+                        if places == 0 {
+                            return false;
+                        }
+                        if places & (places - 1) == 0 && places & unsolved != 0 {
+                            self.place(digit, band, places.trailing_zeros() as usize);
+                            continue 'search;
+                        }
+                    }
+                    for shift in [0, 9, 18] {
+                        let row = (mask >> shift) & 0x1ff;
+                        twice |= once & row;
+                        once |= row;
+                    }
+                }
+                if once != 0x1ff {
+                    return false;
+                }
+                let mut singles = once & !twice;
+                while singles != 0 {
+                    let column = COLUMN << singles.trailing_zeros();
+                    singles &= singles - 1;
+                    for (band, mask) in masks.iter().enumerate() {
+                        let place = mask & column;
+                        if place & self.unsolved[band] != 0 {
+                            self.place(digit, band, place.trailing_zeros() as usize);
+                            continue 'search;
+                        }
+                    }
+                }
+            }
+            if !placed {
+                return true;
+            }
+        }
+    }
+
+    /// The unsolved cell with the fewest digits left, as band and position, with a mask
+    /// of those digits.
+    fn branch_cell(&self) -> (usize, usize, u16) {
+        let mut best = (0, 0, 0u16);
+        let mut fewest = 10;
+        for band in 0..3 {
+            let mut unsolved = self.unsolved[band];
+            while unsolved != 0 {
+                let position = unsolved.trailing_zeros() as usize;
+                unsolved &= unsolved - 1;
+                let mut options = 0u16;
+                for (digit, masks) in self.candidates.iter().enumerate() {
+                    options |= (((masks[band] >> position) & 1) as u16) << digit;
+                }
+                let count = options.count_ones();
+                if count < fewest {
+                    fewest = count;
+                    best = (band, position, options);
+                    if count == 2 {
+                        return best;
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+/// A guess: the board before it, the cell guessed and the digits not tried yet.
+#[derive(Clone, Copy)]
+struct Frame {
+    board: Board,
+    band: usize,
+    position: usize,
+    options: u16,
+}
+
+/// Search over bitboards with the path of guesses on a fixed stack, so solving a sudoku
+/// does not allocate. The search stops at each solution and goes on from there when it
+/// is asked for the next one.
+pub(crate) struct BitboardSearch {
+    root: Board,
+    stack: [Frame; 81],
+    depth: usize,
+    started: bool,
+    solution: Board,
+}
+
+impl BitboardSearch {
+    pub(crate) fn new(sudoku: &Sudoku) -> Self {
+        let root = Board::from_sudoku(sudoku);
+        BitboardSearch {
+            root,
+            stack: [Frame {
+                board: root,
+                band: 0,
+                position: 0,
+                options: 0,
+            }; 81],
+            depth: 0,
+            started: false,
+            solution: root,
+        }
+    }
+
+    /// Guess on the board, or take it as the solution when it is full.
+    fn push(&mut self, board: Board, budget: &mut SearchBudget) -> Result<bool, SearchFailure> {
+        if board.is_solved() {
+            self.solution = board;
+            return Ok(true);
+        }
+        budget.spend()?;
+        let (band, position, options) = board.branch_cell();
+        self.stack[self.depth] = Frame {
+            board,
+            band,
+            position,
+            options,
+        };
+        self.depth += 1;
+        Ok(false)
+    }
+
+    pub(crate) fn next_solution(&mut self, budget: &mut SearchBudget) -> Result<(), SearchFailure> {
+        if !self.started {
+            self.started = true;
+            let mut board = self.root;
+            if board.propagate() && self.push(board, budget)? {
+                return Ok(());
+            }
+        }
+        while self.depth > 0 {
+            let frame = &mut self.stack[self.depth - 1];
+            if frame.options == 0 {
+                self.depth -= 1;
+                continue;
+            }
+            let digit = frame.options.trailing_zeros() as usize;
+            frame.options &= frame.options - 1;
+            let mut board = frame.board;
+            board.place(digit, frame.band, frame.position);
+            if board.propagate() && self.push(board, budget)? {
+                return Ok(());
+            }
+        }
+        Err(SearchFailure::Exhausted)
+    }
+
+    /// Fill the empty cells of the board with the last solution found.
+    pub(crate) fn fill(&self, sudoku: &mut Sudoku) {
+        for cell in 0..81 {
+            if sudoku.board[cell] == 0 {
+                let digit = self.solution.digit_at(cell / 27, 1 << (cell % 27)).unwrap();
+                sudoku.place(cell / 9, cell % 9, digit as u8 + 1);
+            }
+        }
+    }
+}
+
+impl Sudoku {
+    pub(crate) fn solve_with_bitboards(
+        &mut self,
+        budget: &mut SearchBudget,
+    ) -> Result<(), SearchFailure> {
+        let mut search = BitboardSearch::new(self);
+        search.next_solution(budget)?;
+        search.fill(self);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::solver::bitboard::{Board, BAND, BLOCKS, COLUMN, ROWS};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn place_clears_row_column_and_block() {
+        let mut board = Board::new();
+        // The 5 in the second row, fifth column.
+        board.place(4, 0, 13);
+        assert_eq!(
+            board.candidates[4][0],
+            (1 << 13) | ((ROWS[0] | ROWS[2]) & !BLOCKS[1])
+        );
+        assert_eq!(board.candidates[4][1], BAND & !(COLUMN << 4));
+        assert_eq!(board.candidates[0][0], BAND & !(1 << 13));
+        assert_eq!(board.unsolved[0], BAND & !(1 << 13));
+    }
+
+    #[test]
+    fn propagate_singles() {
+        let sudoku = Sudoku::create_board(
+            "530070000\
+             600195000\
+             098000060\
+             800060003\
+             400803001\
+             700020006\
+             060000280\
+             000419005\
+             000080079"
+                .to_string(),
+        )
+        .unwrap();
+        let mut board = Board::from_sudoku(&sudoku);
+        assert!(board.propagate());
+        assert!(board.is_solved());
+    }
+}
@@ -0,0 +1,250 @@
+use crate::sudoku::solver::{SearchBudget, SearchFailure};
+use crate::sudoku::{digits, Sudoku};
+
+/// The node every column header hangs off.
+const ROOT: usize = 0;
+/// One column for each cell, and for each digit in each row, column and block.
+const COLUMNS: usize = 324;
+
+/// A sudoku as an exact cover problem, solved with Knuth's Algorithm X on dancing links.
+///
+/// Every row of the matrix is a digit in a cell and covers four columns: the cell, and
+/// the digit in its row, column and block. A solution picks 81 rows that cover every
+/// column exactly once. The nodes live in flat vectors: node 0 is the root, nodes 1 to
+/// 324 are the column headers and the nodes of the rows follow.
+///
+/// The rows picked so far are kept on a stack, so the search can stop at a solution and
+/// carry on from there when the next one is asked for.
+pub(crate) struct DancingLinks {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The column header of each node.
+    column: Vec<usize>,
+    /// The number of nodes in each column, indexed by header.
+    size: Vec<usize>,
+    /// The cell times 9 plus the digit minus 1 of the row a node is in.
+    candidate: Vec<usize>,
+    stack: Vec<usize>,
+    started: bool,
+}
+
+impl DancingLinks {
+    /// Build the matrix for a board. A filled cell only gets a row for its value, an
+    /// empty cell gets a row for each digit its peers leave open.
+    pub(crate) fn new(sudoku: &Sudoku) -> Self {
+        let mut links = DancingLinks {
+            left: Vec::with_capacity(COLUMNS + 1 + 729 * 4),
+            right: Vec::with_capacity(COLUMNS + 1 + 729 * 4),
+            up: Vec::with_capacity(COLUMNS + 1 + 729 * 4),
+            down: Vec::with_capacity(COLUMNS + 1 + 729 * 4),
+            column: Vec::with_capacity(COLUMNS + 1 + 729 * 4),
+            size: vec![0; COLUMNS + 1],
+            candidate: Vec::with_capacity(COLUMNS + 1 + 729 * 4),
+            stack: Vec::with_capacity(81),
+            started: false,
+        };
+        for header in 0..=COLUMNS {
+            links.left.push((header + COLUMNS) % (COLUMNS + 1));
+            links.right.push((header + 1) % (COLUMNS + 1));
+            links.up.push(header);
+            links.down.push(header);
+            links.column.push(header);
+            links.candidate.push(0);
+        }
+        for cell in 0..81 {
+            let (row, column) = (cell / 9, cell % 9);
+            let options = match sudoku.board[cell] {
+                0 => sudoku.candidates(row, column),
+                digit => 1 << (digit - 1),
+            };
+            for digit in digits(options) {
+                links.add_row(cell, digit);
+            }
+        }
+        links
+    }
+
+    fn add_row(&mut self, cell: usize, digit: u8) {
+        let (row, column) = (cell / 9, cell % 9);
+        let block = Sudoku::block_index(row, column);
+        let offset = digit as usize - 1;
+        let first = self.left.len();
+        for (index, constraint) in [
+            cell,
+            81 + row * 9 + offset,
+            162 + column * 9 + offset,
+            243 + block * 9 + offset,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let node = first + index;
+            let header = constraint + 1;
+            self.left
+                .push(if index == 0 { first + 3 } else { node - 1 });
+            self.right.push(if index == 3 { first } else { node + 1 });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            self.column.push(header);
+            self.candidate.push(cell * 9 + offset);
+            let above = self.up[header];
+            self.down[above] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+        }
+    }
+
+    /// Take a column out of the header list, together with every row that has a node in
+    /// it.
+    fn cover(&mut self, header: usize) {
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = right;
+        self.left[right] = left;
+        let mut row = self.down[header];
+        while row != header {
+            let mut node = self.right[row];
+            while node != row {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    /// Undo [`DancingLinks::cover`], in the reverse order.
+    fn uncover(&mut self, header: usize) {
+        let mut row = self.up[header];
+        while row != header {
+            let mut node = self.left[row];
+            while node != row {
+                self.size[self.column[node]] += 1;
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = node;
+                self.up[down] = node;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = header;
+        self.left[right] = header;
+    }
+
+    /// Pick a row: cover the other columns it has a node in. Its own column is covered
+    /// already.
+    fn select(&mut self, row: usize) {
+        self.stack.push(row);
+        let mut node = self.right[row];
+        while node != row {
+            self.cover(self.column[node]);
+            node = self.right[node];
+        }
+    }
+
+    /// Undo [`DancingLinks::select`] for the last row picked and return it.
+    fn deselect(&mut self) -> Option<usize> {
+        let row = self.stack.pop()?;
+        let mut node = self.left[row];
+        while node != row {
+            self.uncover(self.column[node]);
+            node = self.left[node];
+        }
+        Some(row)
+    }
+
+    /// The column with the fewest rows left, which keeps the search tree narrow.
+    fn choose_column(&self) -> usize {
+        let mut best = self.right[ROOT];
+        let mut header = self.right[best];
+        while header != ROOT {
+            if self.size[header] < self.size[best] {
+                best = header;
+            }
+            header = self.right[header];
+        }
+        best
+    }
+
+    /// Search on to the next solution. The first call starts the search, later calls
+    /// continue after the solution found before.
+    pub(crate) fn next_solution(&mut self, budget: &mut SearchBudget) -> Result<(), SearchFailure> {
+        let mut descend = !self.started;
+        self.started = true;
+        loop {
+            if descend {
+                if self.right[ROOT] == ROOT {
+                    return Ok(());
+                }
+                budget.spend()?;
+                let header = self.choose_column();
+                self.cover(header);
+                let row = self.down[header];
+                if row == header {
+                    self.uncover(header);
+                    descend = false;
+                } else {
+                    self.select(row);
+                }
+                continue;
+            }
+            let row = self.deselect().ok_or(SearchFailure::Exhausted)?;
+            let header = self.column[row];
+            let next = self.down[row];
+            if next == header {
+                self.uncover(header);
+            } else {
+                self.select(next);
+                descend = true;
+            }
+        }
+    }
+
+    /// Fill the empty cells of the board with the rows of the last solution found.
+    pub(crate) fn fill(&self, sudoku: &mut Sudoku) {
+        for row in self.stack.iter() {
+            let candidate = self.candidate[*row];
+            let cell = candidate / 9;
+            if sudoku.board[cell] == 0 {
+                sudoku.place(cell / 9, cell % 9, (candidate % 9) as u8 + 1);
+            }
+        }
+    }
+}
+
+impl Sudoku {
+    pub(crate) fn solve_with_dancing_links(
+        &mut self,
+        budget: &mut SearchBudget,
+    ) -> Result<(), SearchFailure> {
+        let mut links = DancingLinks::new(self);
+        links.next_solution(budget)?;
+        links.fill(self);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::solver::dancing_links::DancingLinks;
+    use crate::sudoku::solver::SearchBudget;
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn build_exact_cover_matrix() {
+        let sudoku = Sudoku::create_board("0".repeat(81)).unwrap();
+        let links = DancingLinks::new(&sudoku);
+        assert_eq!(links.left.len(), 1 + 324 + 729 * 4);
+        assert!(links.size[1..].iter().all(|size| *size == 9));
+
+        let mut solved = sudoku.clone();
+        let mut links = links;
+        links.next_solution(&mut SearchBudget::unlimited()).unwrap();
+        links.fill(&mut solved);
+        assert!(solved.check_sudoku_completed());
+    }
+}
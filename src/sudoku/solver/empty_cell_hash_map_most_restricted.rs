@@ -1,64 +1,81 @@
-use crate::sudoku::Sudoku;
-use std::collections::{HashMap, HashSet};
+use crate::sudoku::solver::{SearchBudget, SearchFailure};
+use crate::sudoku::{digits, Sudoku};
+use std::collections::HashMap;
+
+/// Every option that was crossed out of the possible value map, as the cell and the bit
+/// of the value, so the map can be restored when backtracking.
+type EliminationTrail = Vec<((usize, usize), u16)>;
 
 impl Sudoku {
-    /// Solve the sudoku by creating a hash set of all options each cell has instead of
-    /// calculating that again and again.
-    pub fn solve_with_storing_empty_cell_options(&mut self) {
+    /// Solve the sudoku by creating a hash map with a bit mask of all options each cell
+    /// has instead of calculating that again and again.
+    pub(crate) fn solve_with_storing_empty_cell_options(
+        &mut self,
+        budget: &mut SearchBudget,
+    ) -> Result<(), SearchFailure> {
         let mut possible_value_map = self.create_hash_map_of_all_possible_values();
-        let _ = self.pick_values_from_possible_values_map(&mut possible_value_map);
+        let mut trail = EliminationTrail::new();
         // Get entry with shortest value
         // Fill in, and cross out this option from other entries in the same row/col
-        // Does it make more sense to let the values be a set instead of a vec?
-        // We only add or remove something, or check if a char is present.
+        self.pick_values_from_possible_values_map(&mut possible_value_map, &mut trail, budget)
+            .map(|_| ())
     }
 
+    /// Fill the cell with the fewest options with each of its options in turn and
+    /// recurse. Every option that is crossed out of the map is pushed on the trail, and
+    /// when an option fails the trail is replayed backwards so the map is exactly as it
+    /// was before the option was tried.
     fn pick_values_from_possible_values_map(
         &mut self,
-        possible_value_map: &mut HashMap<(usize, usize), HashSet<char>>,
-    ) -> Result<&Self, ()> {
-        if possible_value_map.len() > 0 {
-            // println!("There is a possible value");
+        possible_value_map: &mut HashMap<(usize, usize), u16>,
+        trail: &mut EliminationTrail,
+        budget: &mut SearchBudget,
+    ) -> Result<&Self, SearchFailure> {
+        if !possible_value_map.is_empty() {
+            budget.spend()?;
             let (row, col) = self.get_cell_with_fewest_options(possible_value_map);
             let possible_values = possible_value_map.remove(&(row, col)).unwrap();
-            // println!("Possible values ({}, {}):  {:?}", row, col, possible_values);
-            for possible_value in possible_values.iter() {
-                // println!("Setting value ({}, {}): {}", row, col, possible_value);
-                self.board[row][col] = possible_value.clone();
-                self.update_possible_value_map_after_selection(
+            for possible_value in digits(possible_values) {
+                let trail_length = trail.len();
+                self.place(row, col, possible_value);
+                let consistent = Sudoku::update_possible_value_map_after_selection(
                     possible_value_map,
+                    trail,
                     (row, col),
                     possible_value,
                 );
-                match self.pick_values_from_possible_values_map(possible_value_map) {
-                    Ok(_) => {
-                        return Ok(self);
-                    }
-                    Err(_) => {
-                        // TODO Put possible value vec back into the hashmap;
-                        // TODO Undo update
-                        self.board[row][col] = '0';
+                if consistent {
+                    match self.pick_values_from_possible_values_map(
+                        possible_value_map,
+                        trail,
+                        budget,
+                    ) {
+                        Ok(_) => return Ok(self),
+                        Err(SearchFailure::Exhausted) => {}
+                        Err(SearchFailure::Aborted) => return Err(SearchFailure::Aborted),
                     }
-                };
+                }
+                Sudoku::undo_eliminations(possible_value_map, trail, trail_length);
+                self.unplace(row, col);
             }
-            return Err(());
+            possible_value_map.insert((row, col), possible_values);
+            return Err(SearchFailure::Exhausted);
         } else if self.check_sudoku_completed() {
             return Ok(self);
         }
-        Err(())
+        Err(SearchFailure::Exhausted)
     }
 
-    fn create_hash_map_of_all_possible_values(&self) -> HashMap<(usize, usize), HashSet<char>> {
+    /// Map every empty cell to a bit mask of its options. A cell without options stays
+    /// in the map with an empty mask, so the search fails on it straight away.
+    fn create_hash_map_of_all_possible_values(&self) -> HashMap<(usize, usize), u16> {
         let mut possible_value_map = HashMap::new();
-        for i in 0..self.board.len() {
-            for j in 0..self.board[i].len() {
-                if self.board[i][j] != '0' {
+        for i in 0..9 {
+            for j in 0..9 {
+                if self.digit(i, j) != 0 {
                     continue;
                 }
-                let possible_values = self.check_possible_values(i, j);
-                if possible_values.len() != 0 {
-                    possible_value_map.insert((i, j), HashSet::from_iter(possible_values));
-                }
+                possible_value_map.insert((i, j), self.candidates(i, j));
             }
         }
         possible_value_map
@@ -66,41 +83,134 @@ impl Sudoku {
 
     fn get_cell_with_fewest_options(
         &self,
-        possible_value_map: &HashMap<(usize, usize), HashSet<char>>,
+        possible_value_map: &HashMap<(usize, usize), u16>,
     ) -> (usize, usize) {
         let mut fewest_option_count = 10;
         let mut cell_with_fewest_possibilities = (0, 0);
         for (cell_index, possibilities) in possible_value_map.iter() {
-            if possibilities.len() < fewest_option_count {
-                fewest_option_count = possibilities.len();
+            if possibilities.count_ones() < fewest_option_count {
+                fewest_option_count = possibilities.count_ones();
                 cell_with_fewest_possibilities = *cell_index;
             }
         }
         cell_with_fewest_possibilities
     }
 
+    /// Cross the choice out of the options of every empty cell in the same row, column
+    /// and block, recording each removal on the trail. Returns false when one of those
+    /// cells has no options left, since the choice can then never lead to a solution.
     fn update_possible_value_map_after_selection(
-        &self,
-        possible_value_map: &mut HashMap<(usize, usize), HashSet<char>>,
+        possible_value_map: &mut HashMap<(usize, usize), u16>,
+        trail: &mut EliminationTrail,
         cell_index: (usize, usize),
-        choice: &char,
-    ) {
+        choice: u8,
+    ) -> bool {
         let (row, col) = cell_index;
+        let bit = 1 << (choice - 1);
+        let mut consistent = true;
         for i in 0..9 {
-            match possible_value_map.get_mut(&(row, i)) {
-                Some(value) => value.remove(choice),
-                None => false,
-            };
-            match possible_value_map.get_mut(&(i, col)) {
-                Some(value) => value.remove(choice),
-                None => false,
-            };
-            match possible_value_map.get_mut(&(i / 3 + (row / 3) * 3, i % 3 + (col / 3) * 3)) {
-                Some(value) => value.remove(choice),
-                None => false,
-            };
+            for peer in [
+                (row, i),
+                (i, col),
+                (i / 3 + (row / 3) * 3, i % 3 + (col / 3) * 3),
+            ] {
+                if let Some(value) = possible_value_map.get_mut(&peer) {
+                    if *value & bit != 0 {
+                        *value &= !bit;
+                        trail.push((peer, bit));
+                        consistent &= *value != 0;
+                    }
+                }
+            }
         }
-        // We might want to keep track of where we removed something to make
-        // backtracking easier
+        consistent
+    }
+
+    /// Put every option removed since the trail had the given length back in the map.
+    fn undo_eliminations(
+        possible_value_map: &mut HashMap<(usize, usize), u16>,
+        trail: &mut EliminationTrail,
+        trail_length: usize,
+    ) {
+        while trail.len() > trail_length {
+            let (cell_index, bit) = trail.pop().unwrap();
+            if let Some(value) = possible_value_map.get_mut(&cell_index) {
+                *value |= bit;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::solver::SearchBudget;
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn solve_hard_sudoku() {
+        let mut sudoku = Sudoku::create_board(
+            "800000000\
+             003600000\
+             070090200\
+             050007000\
+             000045700\
+             000100030\
+             001000068\
+             008500010\
+             090000400"
+                .to_string(),
+        )
+        .unwrap();
+        sudoku
+            .solve_with_storing_empty_cell_options(&mut SearchBudget::unlimited())
+            .unwrap();
+        assert!(sudoku.check_sudoku_completed());
+        assert_eq!(
+            sudoku.to_line(),
+            "812753649943682175675491283154237896369845721287169534521974368438526917796318452"
+        );
+    }
+
+    #[test]
+    fn restore_map_after_failed_selection() {
+        let sudoku = Sudoku::create_board(
+            "012345600\
+             000000007\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000"
+                .to_string(),
+        )
+        .unwrap();
+        let mut possible_value_map = sudoku.create_hash_map_of_all_possible_values();
+        let original_map = possible_value_map.clone();
+        let mut trail = Vec::new();
+        // An 8 in the top left corner leaves only 9 for the two cells on the right of the
+        // row, so a 9 in one of them leaves no option for the other.
+        possible_value_map.remove(&(0, 0));
+        assert!(Sudoku::update_possible_value_map_after_selection(
+            &mut possible_value_map,
+            &mut trail,
+            (0, 0),
+            8,
+        ));
+        possible_value_map.remove(&(0, 7));
+        assert!(!Sudoku::update_possible_value_map_after_selection(
+            &mut possible_value_map,
+            &mut trail,
+            (0, 7),
+            9,
+        ));
+        assert_eq!(possible_value_map[&(0, 8)], 0);
+
+        Sudoku::undo_eliminations(&mut possible_value_map, &mut trail, 0);
+        possible_value_map.insert((0, 0), original_map[&(0, 0)]);
+        possible_value_map.insert((0, 7), original_map[&(0, 7)]);
+        assert!(trail.is_empty());
+        assert_eq!(possible_value_map, original_map);
     }
 }
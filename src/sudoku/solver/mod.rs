@@ -1,48 +1,196 @@
-use crate::sudoku::Sudoku;
-use std::collections::HashSet;
+use crate::sudoku::{digits, Sudoku};
+use std::str::FromStr;
 
 mod simple_solver;
 mod most_restricted_cell_first;
 mod empty_cell_hash_map_most_restricted;
+mod batch;
+mod bitboard;
+mod dancing_links;
+mod parallel;
+mod propagation;
+mod solution_counter;
+mod solution_iterator;
 
+pub use batch::{solve_batch, BatchOptions, BatchResult};
+pub use solution_counter::Uniqueness;
+pub use solution_iterator::Solutions;
+
+/// The backtracking strategies a [`Sudoku`] can be solved with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SolverAlgorithm {
+    /// Fill the empty cells from left to right, top to bottom.
     Simple,
+    /// Fill the cell with the fewest options first.
     MostRestricted,
+    /// Fill the cell with the fewest options first, keeping the options of all empty
+    /// cells in a hash map.
     EmptyCellHashMap,
+    /// Treat the sudoku as an exact cover problem with 324 constraints and solve it with
+    /// Knuth's Algorithm X on dancing links.
+    DancingLinks,
+    /// Encode the sudoku as a boolean formula and solve it with the SAT solver of the
+    /// crate, see [`crate::sudoku::sat`].
+    Sat,
+    /// Keep the candidates of every cell, propagate naked and hidden singles after every
+    /// placement and only guess, on the cell with the fewest candidates, when that stalls.
+    Propagation,
+    /// Keep the candidates of each digit as bitboards over the three bands of the grid,
+    /// propagate singles with bit operations and copy the boards on a guess. The fastest
+    /// of the solvers, and it does not allocate.
+    Bitboard,
+}
+
+impl SolverAlgorithm {
+    /// Names accepted on the command line, in the order of the enum variants.
+    pub const NAMES: [&'static str; 7] = [
+        "simple",
+        "most-restricted",
+        "hash-map",
+        "dancing-links",
+        "sat",
+        "propagation",
+        "bitboard",
+    ];
+
+    /// The name of the algorithm as used on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SolverAlgorithm::Simple => "simple",
+            SolverAlgorithm::MostRestricted => "most-restricted",
+            SolverAlgorithm::EmptyCellHashMap => "hash-map",
+            SolverAlgorithm::DancingLinks => "dancing-links",
+            SolverAlgorithm::Sat => "sat",
+            SolverAlgorithm::Propagation => "propagation",
+            SolverAlgorithm::Bitboard => "bitboard",
+        }
+    }
+}
+
+impl FromStr for SolverAlgorithm {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_lowercase().as_str() {
+            "simple" => Ok(SolverAlgorithm::Simple),
+            "most-restricted" | "most_restricted" => Ok(SolverAlgorithm::MostRestricted),
+            "hash-map" | "hash_map" | "empty-cell-hash-map" => {
+                Ok(SolverAlgorithm::EmptyCellHashMap)
+            }
+            "dancing-links" | "dancing_links" | "dlx" => Ok(SolverAlgorithm::DancingLinks),
+            "sat" => Ok(SolverAlgorithm::Sat),
+            "propagation" | "norvig" => Ok(SolverAlgorithm::Propagation),
+            "bitboard" | "bitboards" => Ok(SolverAlgorithm::Bitboard),
+            _ => Err(format!(
+                "unknown algorithm '{name}', expected one of: {}",
+                SolverAlgorithm::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// How a call to [`Sudoku::solve_sudoku`] ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveOutcome {
+    /// The board now holds a solution.
+    Solved,
+    /// The sudoku has no solution. The board is left unchanged.
+    Unsolvable,
+    /// The search hit its limit before finding a solution. The board is left unchanged.
+    Aborted,
+}
+
+/// Why a search stopped without finding a solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SearchFailure {
+    /// Every option was tried.
+    Exhausted,
+    /// The search budget ran out.
+    Aborted,
+}
+
+/// The number of search nodes a solver may still visit.
+pub(crate) struct SearchBudget {
+    nodes_left: Option<u64>,
+}
+
+impl SearchBudget {
+    pub(crate) fn unlimited() -> Self {
+        SearchBudget { nodes_left: None }
+    }
+
+    pub(crate) fn with_node_limit(node_limit: u64) -> Self {
+        SearchBudget {
+            nodes_left: Some(node_limit),
+        }
+    }
+
+    /// Account for visiting one more node, failing once the budget is used up.
+    pub(crate) fn spend(&mut self) -> Result<(), SearchFailure> {
+        match self.nodes_left.as_mut() {
+            Some(0) => Err(SearchFailure::Aborted),
+            Some(nodes_left) => {
+                *nodes_left -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
 }
 
 impl Sudoku {
-    pub fn solve_sudoku(&mut self, algorithm: &SolverAlgorithm) {
-        match algorithm {
-            SolverAlgorithm::Simple => self.simple_solver(),
-            SolverAlgorithm::MostRestricted => self.solve_from_most_restricted_cell(),
-            SolverAlgorithm::EmptyCellHashMap => self.solve_with_storing_empty_cell_options(),
+    /// Solve the board in place with the given algorithm. If there is no solution the
+    /// board is left as it was.
+    pub fn solve_sudoku(&mut self, algorithm: &SolverAlgorithm) -> SolveOutcome {
+        self.solve_with_budget(algorithm, &mut SearchBudget::unlimited())
+    }
+
+    /// Solve the board in place like [`Sudoku::solve_sudoku`], but give up with
+    /// [`SolveOutcome::Aborted`] after visiting `node_limit` nodes of the search tree.
+    pub fn solve_sudoku_with_node_limit(
+        &mut self,
+        algorithm: &SolverAlgorithm,
+        node_limit: u64,
+    ) -> SolveOutcome {
+        self.solve_with_budget(algorithm, &mut SearchBudget::with_node_limit(node_limit))
+    }
+
+    fn solve_with_budget(
+        &mut self,
+        algorithm: &SolverAlgorithm,
+        budget: &mut SearchBudget,
+    ) -> SolveOutcome {
+        let original = self.clone();
+        let result = match algorithm {
+            SolverAlgorithm::Simple => self.simple_solver(budget),
+            SolverAlgorithm::MostRestricted => self.solve_from_most_restricted_cell(budget),
+            SolverAlgorithm::EmptyCellHashMap => {
+                self.solve_with_storing_empty_cell_options(budget)
+            }
+            SolverAlgorithm::DancingLinks => self.solve_with_dancing_links(budget),
+            SolverAlgorithm::Sat => self.solve_with_sat(budget),
+            SolverAlgorithm::Propagation => self.solve_with_propagation(budget),
+            SolverAlgorithm::Bitboard => self.solve_with_bitboards(budget),
+        };
+        match result {
+            Ok(()) => SolveOutcome::Solved,
+            Err(failure) => {
+                *self = original;
+                match failure {
+                    SearchFailure::Exhausted => SolveOutcome::Unsolvable,
+                    SearchFailure::Aborted => SolveOutcome::Aborted,
+                }
+            }
         }
     }
 
     /// Produce a vector of values that could be filled in the cell passed by the index
     /// based on which values are directly blocked by other cells in the same row, column,
     /// or block.
-    fn check_possible_values(&self, row: usize, column: usize) -> Vec<char> {
-        let mut existing_row_values: HashSet<char> = HashSet::new();
-        let mut existing_column_values: HashSet<char> = HashSet::new();
-        let mut existing_block_values: HashSet<char> = HashSet::new();
-        for i in 0..9 {
-            let row_value = self.board[row][i];
-            let col_value = self.board[i][column];
-            let block_value = self.board[i / 3 + (row / 3) * 3][i % 3 + (column / 3) * 3];
-            existing_row_values.insert(row_value.clone());
-            existing_column_values.insert(col_value.clone());
-            existing_block_values.insert(block_value.clone());
-        }
-        existing_row_values.extend(&existing_column_values);
-        existing_row_values.extend(&existing_block_values);
-        let possible_values = vec!['1', '2', '3', '4', '5', '6', '7', '8', '9']
-            .iter()
-            .filter(|s| !existing_row_values.contains(s))
-            .cloned()
-            .collect();
-        return possible_values;
+    pub fn check_possible_values(&self, row: usize, column: usize) -> Vec<char> {
+        digits(self.candidates(row, column))
+            .map(|digit| (b'0' + digit) as char)
+            .collect()
     }
 }
 
@@ -50,6 +198,103 @@ impl Sudoku {
 mod tests {
     use super::*;
 
+    #[test]
+    fn solve_sudoku_with_every_algorithm() {
+        for algorithm in [
+            SolverAlgorithm::Simple,
+            SolverAlgorithm::MostRestricted,
+            SolverAlgorithm::EmptyCellHashMap,
+            SolverAlgorithm::DancingLinks,
+            SolverAlgorithm::Sat,
+            SolverAlgorithm::Propagation,
+            SolverAlgorithm::Bitboard,
+        ] {
+            let mut sudoku = Sudoku::create_board(
+                "530070000\
+                 600195000\
+                 098000060\
+                 800060003\
+                 400803001\
+                 700020006\
+                 060000280\
+                 000419005\
+                 000080079"
+                    .to_string(),
+            )
+            .unwrap();
+            assert_eq!(sudoku.solve_sudoku(&algorithm), SolveOutcome::Solved);
+            assert!(sudoku.check_sudoku_completed());
+            assert_eq!(sudoku.value(0, 2), '4');
+        }
+    }
+
+    #[test]
+    fn report_unsolvable_sudoku() {
+        for algorithm in [
+            SolverAlgorithm::Simple,
+            SolverAlgorithm::MostRestricted,
+            SolverAlgorithm::EmptyCellHashMap,
+            SolverAlgorithm::DancingLinks,
+            SolverAlgorithm::Sat,
+            SolverAlgorithm::Propagation,
+            SolverAlgorithm::Bitboard,
+        ] {
+            let mut sudoku = Sudoku::create_board(
+                "012345678\
+                 900000000\
+                 000000000\
+                 000000000\
+                 000000000\
+                 000000000\
+                 000000000\
+                 000000000\
+                 000000000"
+                    .to_string(),
+            )
+            .unwrap();
+            let original = sudoku.clone();
+            assert_eq!(sudoku.solve_sudoku(&algorithm), SolveOutcome::Unsolvable);
+            assert_eq!(sudoku, original);
+        }
+    }
+
+    #[test]
+    fn abort_when_node_limit_is_reached() {
+        for algorithm in [
+            SolverAlgorithm::Simple,
+            SolverAlgorithm::MostRestricted,
+            SolverAlgorithm::EmptyCellHashMap,
+            SolverAlgorithm::DancingLinks,
+            SolverAlgorithm::Sat,
+            SolverAlgorithm::Propagation,
+            SolverAlgorithm::Bitboard,
+        ] {
+            let mut sudoku = Sudoku::create_board(
+                "800000000\
+                 003600000\
+                 070090200\
+                 050007000\
+                 000045700\
+                 000100030\
+                 001000068\
+                 008500010\
+                 090000400"
+                    .to_string(),
+            )
+            .unwrap();
+            let original = sudoku.clone();
+            assert_eq!(
+                sudoku.solve_sudoku_with_node_limit(&algorithm, 10),
+                SolveOutcome::Aborted
+            );
+            assert_eq!(sudoku, original);
+            assert_eq!(
+                sudoku.solve_sudoku_with_node_limit(&algorithm, 10_000_000),
+                SolveOutcome::Solved
+            );
+        }
+    }
+
     #[test]
     fn get_possible_values_of_empty_sudoku() {
         let sudoku = Sudoku::create_board(
@@ -265,7 +510,7 @@ mod tests {
         ];
         for (index, sudoku) in sudokus.iter().enumerate() {
             assert_eq!(
-                sudoku.check_possible_values(index * 3 + 0, index * 3),
+                sudoku.check_possible_values(index * 3, index * 3),
                 vec!['5', '6', '7', '8', '9']
             );
             assert_eq!(
@@ -1,8 +1,9 @@
-use crate::sudoku::Sudoku;
+use crate::sudoku::solver::{SearchBudget, SearchFailure};
+use crate::sudoku::{digits, Sudoku};
 
 impl Sudoku {
-    pub fn solve_from_most_restricted_cell(&mut self) {
-        self.fill_value_and_check_most_restricted().unwrap();
+    pub(crate) fn solve_from_most_restricted_cell(&mut self, budget: &mut SearchBudget) -> Result<(), SearchFailure> {
+        self.fill_value_and_check_most_restricted(budget).map(|_| ())
     }
 
     /// Solves the sudoku by finding the first cell in the sodoku that has one option or
@@ -11,25 +12,27 @@ impl Sudoku {
     /// the next value for the last cell that was filled in. 
     /// This approach should be faster than the simple solver because it has to do 
     /// fewer backtracks
-    fn fill_value_and_check_most_restricted(&mut self) -> Result<&Self, ()> {
+    fn fill_value_and_check_most_restricted(&mut self, budget: &mut SearchBudget) -> Result<&Self, SearchFailure> {
         if self.check_sudoku_completed() {
             return Ok(self);
         }
+        budget.spend()?;
         if let Ok((row_index, column_index, options)) = self.choose_most_restricted_value() {
-            for option in options.iter() {
-                self.board[row_index][column_index] = *option;
-                match self.fill_value_and_check_most_restricted() {
+            for option in digits(options) {
+                self.place(row_index, column_index, option);
+                match self.fill_value_and_check_most_restricted(budget) {
                     Ok(_) => {
                         return Ok(self);
                     }
-                    Err(_) => {
-                        self.board[row_index][column_index] = '0';
+                    Err(SearchFailure::Exhausted) => {
+                        self.unplace(row_index, column_index);
                     }
+                    Err(SearchFailure::Aborted) => return Err(SearchFailure::Aborted),
                 };
             }
-            return Err(());
+            Err(SearchFailure::Exhausted)
         } else {
-            return Err(());
+            Err(SearchFailure::Exhausted)
         }
     }
 
@@ -37,24 +40,25 @@ impl Sudoku {
     /// Find the first cell that has just one option, or the first cell that has the 
     /// fewest available options.
     /// It iterates from left to right, top to bottom.
-    /// Returns the index of the cell and the values that are possible to place in the
-    /// cell based on which values are directly blocked by other cells in the same row,
-    /// column, or block.
-    fn choose_most_restricted_value(&self) -> Result<(usize, usize, Vec<char>), ()> {
+    /// Returns the index of the cell and a bit mask of the values that are possible to
+    /// place in the cell based on which values are directly blocked by other cells in the
+    /// same row, column, or block.
+    pub(crate) fn choose_most_restricted_value(&self) -> Result<(usize, usize, u16), ()> {
         let mut fewest_options_count = 10;
-        let mut fewest_options_values = (10, 10, Vec::new());
-        for i in 0..self.board.len() {
-            for j in 0..self.board[i].len() {
-                if self.board[i][j] == '0' {
-                    let possible_values = self.check_possible_values(i, j);
-                    if possible_values.len() == 0 {
+        let mut fewest_options_values = (10, 10, 0);
+        for i in 0..9 {
+            for j in 0..9 {
+                if self.digit(i, j) == 0 {
+                    let possible_values = self.candidates(i, j);
+                    let option_count = possible_values.count_ones();
+                    if option_count == 0 {
                         return Err(());
                     }
-                    if possible_values.len() == 1 {
+                    if option_count == 1 {
                         return Ok((i, j, possible_values));
-                    } 
-                    if possible_values.len() < fewest_options_count {
-                        fewest_options_count = possible_values.len();
+                    }
+                    if option_count < fewest_options_count {
+                        fewest_options_count = option_count;
                         fewest_options_values = (i, j, possible_values);
                     }
                 }
@@ -69,7 +73,8 @@ impl Sudoku {
 
 #[cfg(test)]
 mod tests {
-    use crate::sudoku::Sudoku;
+    use crate::sudoku::solver::SearchBudget;
+    use crate::sudoku::{Sudoku, ALL_VALUES};
 
     #[test]
     fn fill_empty_sudoku() {
@@ -86,7 +91,7 @@ mod tests {
                 .to_string(),
         )
         .unwrap();
-        sudoku.fill_value_and_check_most_restricted().unwrap();
+        sudoku.fill_value_and_check_most_restricted(&mut SearchBudget::unlimited()).unwrap();
         assert!(sudoku.check_correctness_of_sudoku());
     }
 
@@ -107,7 +112,7 @@ mod tests {
         .unwrap();
         assert_eq!(
             sudoku.choose_most_restricted_value().unwrap(),
-            (0, 0, vec!['1', '2', '3', '4', '5', '6', '7', '8', '9'])
+            (0, 0, ALL_VALUES)
         );
     }
 
@@ -163,7 +168,7 @@ mod tests {
         .unwrap();
         assert_eq!(
             sudoku.choose_most_restricted_value().unwrap(),
-            (0, 0, vec!['9'])
+            (0, 0, 1 << (9 - 1))
         );
 
         let sudoku = Sudoku::create_board(
@@ -181,7 +186,7 @@ mod tests {
         .unwrap();
         assert_eq!(
             sudoku.choose_most_restricted_value().unwrap(),
-            (5, 5, vec!['4'])
+            (5, 5, 1 << (4 - 1))
         );
     }
 }
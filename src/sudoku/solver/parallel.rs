@@ -0,0 +1,216 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::sudoku::solver::SolveOutcome;
+use crate::sudoku::{digits, Sudoku};
+
+/// The number of subtrees to aim for per thread, so a thread that finishes an easy
+/// subtree early can take over another one.
+const SUBTREES_PER_THREAD: usize = 8;
+
+/// What the workers of a parallel search share: the next subtree to take, the
+/// solutions found so far and the flag that tells everyone to stop.
+struct SharedSearch {
+    next: AtomicUsize,
+    found: AtomicUsize,
+    limit: usize,
+    stop: AtomicBool,
+    first: Mutex<Option<Sudoku>>,
+    /// The nodes the workers visited, added up when they are done.
+    visited: AtomicUsize,
+}
+
+impl SharedSearch {
+    /// Count a solution and keep it if it is the first. Once the limit is reached every
+    /// worker is told to stop.
+    fn record(&self, solution: &Sudoku) {
+        let found = self.found.fetch_add(1, Ordering::Relaxed) + 1;
+        if found > self.limit {
+            return;
+        }
+        if found == 1 {
+            *self.first.lock().unwrap() = Some(solution.clone());
+        }
+        if found == self.limit {
+            self.stop.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Walk the whole subtree below the board, filling the most restricted cell first,
+    /// until it is done or the search is stopped. The nodes are counted in `visited`.
+    fn search(&self, sudoku: &mut Sudoku, visited: &mut usize) {
+        if self.stop.load(Ordering::Relaxed) {
+            return;
+        }
+        *visited += 1;
+        if sudoku.check_sudoku_is_filled() {
+            self.record(sudoku);
+            return;
+        }
+        if let Ok((row, column, options)) = sudoku.choose_most_restricted_value() {
+            for option in digits(options) {
+                sudoku.place(row, column, option);
+                self.search(sudoku, visited);
+                sudoku.unplace(row, column);
+            }
+        }
+    }
+}
+
+impl Sudoku {
+    /// Split the search tree at its shallowest branching points, the options of the
+    /// most restricted cell, until there are enough subtrees to keep the threads busy.
+    /// Solutions met on the way are recorded right away.
+    fn split_search(&self, shared: &SharedSearch, threads: usize) -> Vec<Sudoku> {
+        let mut frontier = vec![self.clone()];
+        while !frontier.is_empty() && frontier.len() < threads * SUBTREES_PER_THREAD {
+            let mut next = Vec::with_capacity(frontier.len() * 2);
+            for sudoku in frontier {
+                if sudoku.check_sudoku_is_filled() {
+                    shared.record(&sudoku);
+                    continue;
+                }
+                if let Ok((row, column, options)) = sudoku.choose_most_restricted_value() {
+                    for option in digits(options) {
+                        let mut child = sudoku.clone();
+                        child.place(row, column, option);
+                        next.push(child);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        frontier
+    }
+
+    /// Search with `threads` workers until `limit` solutions are found or the tree is
+    /// exhausted.
+    fn search_in_parallel(&self, threads: usize, limit: usize) -> SharedSearch {
+        let shared = SharedSearch {
+            next: AtomicUsize::new(0),
+            found: AtomicUsize::new(0),
+            limit,
+            stop: AtomicBool::new(limit == 0),
+            first: Mutex::new(None),
+            visited: AtomicUsize::new(0),
+        };
+        if limit == 0 || !self.check_correctness_of_sudoku() {
+            return shared;
+        }
+        let threads = threads.max(1);
+        let subtrees = self.split_search(&shared, threads);
+        thread::scope(|scope| {
+            for _ in 0..threads.min(subtrees.len()) {
+                scope.spawn(|| {
+                    let mut visited = 0;
+                    while !shared.stop.load(Ordering::Relaxed) {
+                        let index = shared.next.fetch_add(1, Ordering::Relaxed);
+                        match subtrees.get(index) {
+                            Some(subtree) => shared.search(&mut subtree.clone(), &mut visited),
+                            None => break,
+                        }
+                    }
+                    shared.visited.fetch_add(visited, Ordering::Relaxed);
+                });
+            }
+        });
+        shared
+    }
+
+    /// Solve the board in place, searching the subtrees below the first few guesses of
+    /// the most restricted cell first solver on `threads` threads at once. All threads
+    /// stop as soon as one of them finds a solution; for a sudoku with several solutions
+    /// it is not fixed which one that is.
+    pub fn solve_in_parallel(&mut self, threads: usize) -> SolveOutcome {
+        let shared = self.search_in_parallel(threads, 1);
+        match shared.first.into_inner().unwrap() {
+            Some(solution) => {
+                *self = solution;
+                SolveOutcome::Solved
+            }
+            None => SolveOutcome::Unsolvable,
+        }
+    }
+
+    /// Count the solutions like [`Sudoku::count_solutions`], with the subtrees searched on
+    /// `threads` threads at once. The threads stop once `limit` solutions have been found
+    /// between them.
+    pub fn count_solutions_in_parallel(&self, threads: usize, limit: usize) -> usize {
+        let shared = self.search_in_parallel(threads, limit);
+        shared.found.into_inner().min(limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::solver::{SolveOutcome, SolverAlgorithm};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn stop_every_worker_after_the_first_solution() {
+        // The empty board has far too many solutions to search through, so the workers
+        // only finish because the first solution stops them.
+        let empty = Sudoku::create_board("0".repeat(81)).unwrap();
+        let shared = empty.search_in_parallel(4, 1);
+        assert!(shared.stop.into_inner());
+        assert!(shared.found.into_inner() >= 1);
+        assert!(shared.visited.into_inner() < 10_000);
+        assert!(shared
+            .first
+            .into_inner()
+            .unwrap()
+            .unwrap()
+            .check_sudoku_completed());
+    }
+
+    #[test]
+    fn solve_hard_sudoku_on_several_threads() {
+        let sudoku = Sudoku::create_board(
+            "800000000\
+             003600000\
+             070090200\
+             050007000\
+             000045700\
+             000100030\
+             001000068\
+             008500010\
+             090000400"
+                .to_string(),
+        )
+        .unwrap();
+        let mut expected = sudoku.clone();
+        expected.solve_sudoku(&SolverAlgorithm::MostRestricted);
+        for threads in [0, 1, 4] {
+            let mut solved = sudoku.clone();
+            assert_eq!(solved.solve_in_parallel(threads), SolveOutcome::Solved);
+            assert_eq!(solved, expected);
+            assert_eq!(sudoku.count_solutions_in_parallel(threads, 2), 1);
+        }
+    }
+
+    #[test]
+    fn count_solutions_on_several_threads() {
+        let empty = Sudoku::create_board("0".repeat(81)).unwrap();
+        assert_eq!(empty.count_solutions_in_parallel(4, 0), 0);
+        assert_eq!(empty.count_solutions_in_parallel(4, 500), 500);
+
+        let mut unsolvable = Sudoku::create_board(
+            "012345678\
+             900000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000"
+                .to_string(),
+        )
+        .unwrap();
+        let original = unsolvable.clone();
+        assert_eq!(unsolvable.count_solutions_in_parallel(4, 2), 0);
+        assert_eq!(unsolvable.solve_in_parallel(4), SolveOutcome::Unsolvable);
+        assert_eq!(unsolvable, original);
+    }
+}
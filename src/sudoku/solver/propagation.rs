@@ -0,0 +1,198 @@
+use crate::sudoku::logic::{peers, House};
+use crate::sudoku::solver::{SearchBudget, SearchFailure};
+use crate::sudoku::{digits, Sudoku, ALL_VALUES};
+
+/// The candidates of every cell, in the style of Peter Norvig's solver: a cell is solved
+/// once a single candidate is left. The grid is small enough to copy at every guess, so
+/// backtracking needs no undo.
+#[derive(Clone, Copy)]
+struct Candidates([u16; 81]);
+
+impl Candidates {
+    /// The candidates after assigning every given, or `None` when the givens already
+    /// lead to a contradiction.
+    fn from_sudoku(sudoku: &Sudoku) -> Option<Self> {
+        let mut candidates = Candidates([ALL_VALUES; 81]);
+        for (cell, digit) in sudoku.board.iter().enumerate() {
+            if *digit != 0 && !candidates.assign(cell, *digit) {
+                return None;
+            }
+        }
+        Some(candidates)
+    }
+
+    /// Remove every other candidate from the cell. Returns `false` on a contradiction.
+    fn assign(&mut self, cell: usize, digit: u8) -> bool {
+        let others = self.0[cell] & !(1 << (digit - 1));
+        digits(others).all(|other| self.eliminate(cell, other))
+    }
+
+    /// Remove a candidate and propagate: a cell left with one candidate removes it from
+    /// its peers, and a unit left with one place for the digit gets it assigned there.
+    /// Returns `false` on a contradiction.
+    fn eliminate(&mut self, cell: usize, digit: u8) -> bool {
+        let bit = 1 << (digit - 1);
+        if self.0[cell] & bit == 0 {
+            return true;
+        }
+        self.0[cell] &= !bit;
+        let left = self.0[cell];
+        if left == 0 {
+            return false;
+        }
+        if left.count_ones() == 1 {
+            let value = left.trailing_zeros() as u8 + 1;
+            if !peers(cell).iter().all(|peer| self.eliminate(*peer, value)) {
+                return false;
+            }
+        }
+        let units = [
+            House::Row(cell / 9),
+            House::Column(cell % 9),
+            House::Block(Sudoku::block_index(cell / 9, cell % 9)),
+        ];
+        for unit in units {
+            let mut places = unit.cells().into_iter().filter(|c| self.0[*c] & bit != 0);
+            match (places.next(), places.next()) {
+                (None, _) => return false,
+                (Some(place), None) if !self.assign(place, digit) => return false,
+                _ => {}
+            }
+        }
+        true
+    }
+
+    /// The unsolved cell with the fewest candidates, `None` when every cell is solved.
+    fn fewest_candidates(&self) -> Option<usize> {
+        (0..81)
+            .filter(|cell| self.0[*cell].count_ones() > 1)
+            .min_by_key(|cell| self.0[*cell].count_ones())
+    }
+}
+
+/// A guess: the candidates before it, the cell guessed and the digits not tried yet.
+struct Guess {
+    candidates: Candidates,
+    cell: usize,
+    options: u16,
+}
+
+/// Search that guesses on the cell with the fewest candidates and propagates after
+/// every guess. The guesses are kept on a stack, so the search can stop at a solution
+/// and carry on from there when the next one is asked for.
+pub(crate) struct PropagationSearch {
+    /// The candidates after the givens, `None` when they contradict each other.
+    root: Option<Candidates>,
+    stack: Vec<Guess>,
+    started: bool,
+    solution: Option<Candidates>,
+}
+
+impl PropagationSearch {
+    pub(crate) fn new(sudoku: &Sudoku) -> Self {
+        PropagationSearch {
+            root: Candidates::from_sudoku(sudoku),
+            stack: Vec::new(),
+            started: false,
+            solution: None,
+        }
+    }
+
+    /// Guess on the cell with the fewest candidates, or take the candidates as the
+    /// solution when every cell is solved.
+    fn push(
+        &mut self,
+        candidates: Candidates,
+        budget: &mut SearchBudget,
+    ) -> Result<bool, SearchFailure> {
+        let Some(cell) = candidates.fewest_candidates() else {
+            self.solution = Some(candidates);
+            return Ok(true);
+        };
+        budget.spend()?;
+        self.stack.push(Guess {
+            candidates,
+            cell,
+            options: candidates.0[cell],
+        });
+        Ok(false)
+    }
+
+    /// Search on to the next solution. The first call starts the search, later calls
+    /// continue after the solution found before.
+    pub(crate) fn next_solution(&mut self, budget: &mut SearchBudget) -> Result<(), SearchFailure> {
+        if !self.started {
+            self.started = true;
+            if let Some(root) = self.root {
+                if self.push(root, budget)? {
+                    return Ok(());
+                }
+            }
+        }
+        while let Some(guess) = self.stack.last_mut() {
+            if guess.options == 0 {
+                self.stack.pop();
+                continue;
+            }
+            let digit = guess.options.trailing_zeros() as u8 + 1;
+            guess.options &= guess.options - 1;
+            let mut candidates = guess.candidates;
+            if candidates.assign(guess.cell, digit) && self.push(candidates, budget)? {
+                return Ok(());
+            }
+        }
+        Err(SearchFailure::Exhausted)
+    }
+
+    /// Fill the empty cells of the board with the last solution found.
+    pub(crate) fn fill(&self, sudoku: &mut Sudoku) {
+        let solution = self.solution.as_ref().expect("a solution was found");
+        for (cell, mask) in solution.0.iter().enumerate() {
+            if sudoku.board[cell] == 0 {
+                sudoku.place(cell / 9, cell % 9, mask.trailing_zeros() as u8 + 1);
+            }
+        }
+    }
+}
+
+impl Sudoku {
+    /// Solve the sudoku with constraint propagation, only guessing when naked and hidden
+    /// singles are not enough.
+    pub(crate) fn solve_with_propagation(
+        &mut self,
+        budget: &mut SearchBudget,
+    ) -> Result<(), SearchFailure> {
+        let mut search = PropagationSearch::new(self);
+        search.next_solution(budget)?;
+        search.fill(self);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::solver::{SolveOutcome, SolverAlgorithm};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn solve_easy_sudoku_without_guessing() {
+        let mut sudoku = Sudoku::create_board(
+            "530070000\
+             600195000\
+             098000060\
+             800060003\
+             400803001\
+             700020006\
+             060000280\
+             000419005\
+             000080079"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            sudoku.solve_sudoku_with_node_limit(&SolverAlgorithm::Propagation, 0),
+            SolveOutcome::Solved
+        );
+        assert!(sudoku.check_sudoku_completed());
+    }
+}
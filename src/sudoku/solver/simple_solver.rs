@@ -1,8 +1,9 @@
-use crate::sudoku::Sudoku;
+use crate::sudoku::solver::{SearchBudget, SearchFailure};
+use crate::sudoku::{digits, Sudoku};
 
 impl Sudoku {
-    pub fn simple_solver(&mut self) {
-        self.fill_value_and_check().unwrap();
+    pub(crate) fn simple_solver(&mut self, budget: &mut SearchBudget) -> Result<(), SearchFailure> {
+        self.fill_value_and_check(budget).map(|_| ())
     }
 
     /// Solve the sudoku by findin the first empty cell, trying a value that is not directly
@@ -10,39 +11,41 @@ impl Sudoku {
     /// cell, backtrack and try the next value for the last cell that was filled in.
     /// If all options for a call have been tried, backtrack further and try the next option
     /// for the last cell before that.
-    fn fill_value_and_check(&mut self) -> Result<&Self, ()> {
+    fn fill_value_and_check(&mut self, budget: &mut SearchBudget) -> Result<&Self, SearchFailure> {
         if self.check_sudoku_completed() {
             return Ok(self);
         }
+        budget.spend()?;
         if let Ok((row_index, column_index, options)) = self.choose_first_possible_value() {
-            for option in options.iter() {
-                self.board[row_index][column_index] = *option;
-                match self.fill_value_and_check() {
+            for option in digits(options) {
+                self.place(row_index, column_index, option);
+                match self.fill_value_and_check(budget) {
                     Ok(_) => {
                         return Ok(self);
                     }
-                    Err(_) => {
-                        self.board[row_index][column_index] = '0';
+                    Err(SearchFailure::Exhausted) => {
+                        self.unplace(row_index, column_index);
                     }
+                    Err(SearchFailure::Aborted) => return Err(SearchFailure::Aborted),
                 };
             }
-            return Err(());
+            Err(SearchFailure::Exhausted)
         } else {
-            return Err(());
+            Err(SearchFailure::Exhausted)
         }
     }
 
     /// Finds the first possible cell in the sudoku that has not yet been filled
     /// It iterates from left to right, top to bottom
-    /// Returns the index of the cell and the values that are possible to place in the
-    /// cell based on which values are directly blocked by other cells in the same row,
-    /// column, or block.
-    fn choose_first_possible_value(&self) -> Result<(usize, usize, Vec<char>), ()> {
-        for i in 0..self.board.len() {
-            for j in 0..self.board[i].len() {
-                if self.board[i][j] == '0' {
-                    let possible_values = self.check_possible_values(i, j);
-                    if possible_values != [] {
+    /// Returns the index of the cell and a bit mask of the values that are possible to
+    /// place in the cell based on which values are directly blocked by other cells in the
+    /// same row, column, or block.
+    pub(crate) fn choose_first_possible_value(&self) -> Result<(usize, usize, u16), ()> {
+        for i in 0..9 {
+            for j in 0..9 {
+                if self.digit(i, j) == 0 {
+                    let possible_values = self.candidates(i, j);
+                    if possible_values != 0 {
                         return Ok((i, j, possible_values));
                     } else {
                         return Err(());
@@ -57,7 +60,8 @@ impl Sudoku {
 
 #[cfg(test)]
 mod tests {
-    use crate::sudoku::Sudoku;
+    use crate::sudoku::solver::SearchBudget;
+    use crate::sudoku::{Sudoku, ALL_VALUES};
 
     #[test]
     fn fill_empty_sudoku() {
@@ -74,7 +78,7 @@ mod tests {
                 .to_string(),
         )
         .unwrap();
-        sudoku.fill_value_and_check().unwrap();
+        sudoku.fill_value_and_check(&mut SearchBudget::unlimited()).unwrap();
         assert!(sudoku.check_correctness_of_sudoku());
     }
 
@@ -95,7 +99,7 @@ mod tests {
         .unwrap();
         assert_eq!(
             sudoku.choose_first_possible_value().unwrap(),
-            (0, 0, vec!['1', '2', '3', '4', '5', '6', '7', '8', '9'])
+            (0, 0, ALL_VALUES)
         );
     }
 
@@ -151,7 +155,7 @@ mod tests {
         .unwrap();
         assert_eq!(
             sudoku.choose_first_possible_value().unwrap(),
-            (0, 0, vec!['9'])
+            (0, 0, 1 << (9 - 1))
         );
 
         let sudoku = Sudoku::create_board(
@@ -169,7 +173,7 @@ mod tests {
         .unwrap();
         assert_eq!(
             sudoku.choose_first_possible_value().unwrap(),
-            (5, 4, vec!['3', '4'])
+            (5, 4, 1 << (3 - 1) | 1 << (4 - 1))
         );
     }
 
@@ -0,0 +1,131 @@
+use crate::sudoku::solver::SolverAlgorithm;
+use crate::sudoku::Sudoku;
+
+/// Whether a sudoku has no, exactly one or several solutions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Uniqueness {
+    NoSolution,
+    Unique(Sudoku),
+    /// The first two solutions the search found.
+    Multiple(Sudoku, Sudoku),
+}
+
+impl Sudoku {
+    /// Count the solutions of the sudoku, stopping as soon as `limit` solutions have
+    /// been found. A limit of 2 is enough to tell a unique sudoku from an ambiguous one.
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.count_solutions_with(&SolverAlgorithm::MostRestricted, limit)
+    }
+
+    /// Count the solutions like [`Sudoku::count_solutions`], searching with the given
+    /// algorithm.
+    pub fn count_solutions_with(&self, algorithm: &SolverAlgorithm, limit: usize) -> usize {
+        self.solutions(algorithm).take(limit).count()
+    }
+
+    /// Check that the sudoku has exactly one solution.
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+
+    /// Search for up to two solutions and return them, so an ambiguous sudoku can be
+    /// shown with two of its solutions.
+    pub fn uniqueness(&self) -> Uniqueness {
+        let mut solutions = self.solutions(&SolverAlgorithm::MostRestricted);
+        match (solutions.next(), solutions.next()) {
+            (None, _) => Uniqueness::NoSolution,
+            (Some(solution), None) => Uniqueness::Unique(solution),
+            (Some(first), Some(second)) => Uniqueness::Multiple(first, second),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::solver::{SolverAlgorithm, Uniqueness};
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn count_solutions_of_unique_sudoku() {
+        let sudoku = Sudoku::create_board(
+            "530070000\
+             600195000\
+             098000060\
+             800060003\
+             400803001\
+             700020006\
+             060000280\
+             000419005\
+             000080079"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(sudoku.count_solutions(10), 1);
+        assert!(sudoku.has_unique_solution());
+        match sudoku.uniqueness() {
+            Uniqueness::Unique(solution) => {
+                assert!(solution.check_sudoku_completed());
+                assert_eq!(solution.value(0, 2), '4');
+            }
+            other => panic!("expected a unique solution, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn stop_counting_at_limit() {
+        let sudoku = Sudoku::create_board("0".repeat(81)).unwrap();
+        assert_eq!(sudoku.count_solutions(0), 0);
+        assert_eq!(sudoku.count_solutions(5), 5);
+        assert!(!sudoku.has_unique_solution());
+        assert_eq!(
+            sudoku.count_solutions_with(&SolverAlgorithm::DancingLinks, 100),
+            100
+        );
+    }
+
+    #[test]
+    fn return_two_distinct_solutions_of_ambiguous_sudoku() {
+        // The 1s and 4s in the top three rows can be swapped.
+        let sudoku = Sudoku::create_board(
+            "023056789\
+             056789023\
+             789023056\
+             234567891\
+             567891234\
+             891234567\
+             345678912\
+             678912345\
+             912345678"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(sudoku.count_solutions(10), 2);
+        match sudoku.uniqueness() {
+            Uniqueness::Multiple(first, second) => {
+                assert!(first.check_sudoku_completed());
+                assert!(second.check_sudoku_completed());
+                assert_ne!(first, second);
+            }
+            other => panic!("expected two solutions, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn count_no_solutions() {
+        let sudoku = Sudoku::create_board(
+            "012345678\
+             900000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(sudoku.count_solutions(2), 0);
+        assert_eq!(sudoku.uniqueness(), Uniqueness::NoSolution);
+    }
+}
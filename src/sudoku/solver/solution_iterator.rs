@@ -0,0 +1,258 @@
+use crate::sudoku::sat::SatSolutions;
+use crate::sudoku::solver::bitboard::BitboardSearch;
+use crate::sudoku::solver::dancing_links::DancingLinks;
+use crate::sudoku::solver::propagation::PropagationSearch;
+use crate::sudoku::solver::{SearchBudget, SolverAlgorithm};
+use crate::sudoku::Sudoku;
+
+/// A cell the search filled in, with the options that have not been tried yet.
+struct SearchFrame {
+    row: usize,
+    column: usize,
+    options: u16,
+}
+
+/// A search that finds whole solutions on its own instead of filling in one cell at a
+/// time.
+enum WholeSearch {
+    DancingLinks(DancingLinks),
+    Sat(SatSolutions),
+    Bitboard(Box<BitboardSearch>),
+    Propagation(PropagationSearch),
+}
+
+impl WholeSearch {
+    fn new(sudoku: &Sudoku, algorithm: SolverAlgorithm) -> Option<Self> {
+        match algorithm {
+            SolverAlgorithm::DancingLinks => {
+                Some(WholeSearch::DancingLinks(DancingLinks::new(sudoku)))
+            }
+            SolverAlgorithm::Sat => Some(WholeSearch::Sat(SatSolutions::new(sudoku))),
+            SolverAlgorithm::Bitboard => {
+                Some(WholeSearch::Bitboard(Box::new(BitboardSearch::new(sudoku))))
+            }
+            SolverAlgorithm::Propagation => {
+                Some(WholeSearch::Propagation(PropagationSearch::new(sudoku)))
+            }
+            _ => None,
+        }
+    }
+
+    fn next_solution(&mut self, sudoku: &Sudoku) -> Option<Sudoku> {
+        let mut budget = SearchBudget::unlimited();
+        match self {
+            WholeSearch::DancingLinks(links) => {
+                links.next_solution(&mut budget).ok()?;
+                let mut solution = sudoku.clone();
+                links.fill(&mut solution);
+                Some(solution)
+            }
+            WholeSearch::Sat(solutions) => solutions.next_solution(&mut budget).ok(),
+            WholeSearch::Bitboard(search) => {
+                search.next_solution(&mut budget).ok()?;
+                let mut solution = sudoku.clone();
+                search.fill(&mut solution);
+                Some(solution)
+            }
+            WholeSearch::Propagation(search) => {
+                search.next_solution(&mut budget).ok()?;
+                let mut solution = sudoku.clone();
+                search.fill(&mut solution);
+                Some(solution)
+            }
+        }
+    }
+}
+
+/// Iterator over all solutions of a sudoku, created by [`Sudoku::solutions`].
+///
+/// It walks the same search tree as the recursive solvers, but keeps the path to the
+/// current cell on an explicit stack, so the search can stop after each solution and
+/// continue from there when the next one is asked for. The exact cover, SAT,
+/// propagation and bitboard searches keep their own state instead.
+pub struct Solutions {
+    sudoku: Sudoku,
+    algorithm: SolverAlgorithm,
+    stack: Vec<SearchFrame>,
+    started: bool,
+    whole: Option<WholeSearch>,
+}
+
+impl Sudoku {
+    /// Lazily enumerate every solution of the sudoku. The cells are filled in the order
+    /// the given algorithm would fill them; the hash map solver visits the cells in the
+    /// same order as the most restricted cell first solver. The board itself is not
+    /// changed.
+    pub fn solutions(&self, algorithm: &SolverAlgorithm) -> Solutions {
+        Solutions {
+            sudoku: self.clone(),
+            algorithm: *algorithm,
+            stack: Vec::new(),
+            started: false,
+            whole: WholeSearch::new(self, *algorithm),
+        }
+    }
+}
+
+impl Solutions {
+    /// Choose the next cell to fill in, or `None` if some empty cell has no options left
+    /// or the board is full.
+    fn choose_cell(&self) -> Option<SearchFrame> {
+        let choice = match self.algorithm {
+            SolverAlgorithm::Simple => self.sudoku.choose_first_possible_value(),
+            // The hash map only caches the options of the empty cells, the cell it fills
+            // next is the same one.
+            SolverAlgorithm::MostRestricted | SolverAlgorithm::EmptyCellHashMap => {
+                self.sudoku.choose_most_restricted_value()
+            }
+            SolverAlgorithm::DancingLinks
+            | SolverAlgorithm::Sat
+            | SolverAlgorithm::Bitboard
+            | SolverAlgorithm::Propagation => unreachable!(),
+        };
+        choice.ok().map(|(row, column, options)| SearchFrame {
+            row,
+            column,
+            options,
+        })
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Sudoku;
+
+    fn next(&mut self) -> Option<Sudoku> {
+        if let Some(whole) = self.whole.as_mut() {
+            return whole.next_solution(&self.sudoku);
+        }
+        if !self.started {
+            self.started = true;
+            if !self.sudoku.check_correctness_of_sudoku() {
+                return None;
+            }
+            if self.sudoku.check_sudoku_is_filled() {
+                return Some(self.sudoku.clone());
+            }
+            self.stack.extend(self.choose_cell());
+        }
+        while let Some(frame) = self.stack.last_mut() {
+            let (row, column) = (frame.row, frame.column);
+            if frame.options == 0 {
+                self.sudoku.unplace(row, column);
+                self.stack.pop();
+                continue;
+            }
+            let option = frame.options.trailing_zeros() as u8 + 1;
+            frame.options &= frame.options - 1;
+            self.sudoku.unplace(row, column);
+            self.sudoku.place(row, column, option);
+            if self.sudoku.check_sudoku_is_filled() {
+                return Some(self.sudoku.clone());
+            }
+            self.stack.extend(self.choose_cell());
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sudoku::solver::SolverAlgorithm;
+    use crate::sudoku::Sudoku;
+
+    #[test]
+    fn enumerate_all_solutions() {
+        let sudoku = Sudoku::create_board(
+            "023056789\
+             056789023\
+             789023056\
+             234567891\
+             567891234\
+             891234567\
+             345678912\
+             678912345\
+             912345678"
+                .to_string(),
+        )
+        .unwrap();
+        for algorithm in [
+            SolverAlgorithm::Simple,
+            SolverAlgorithm::MostRestricted,
+            SolverAlgorithm::EmptyCellHashMap,
+            SolverAlgorithm::DancingLinks,
+            SolverAlgorithm::Sat,
+            SolverAlgorithm::Propagation,
+            SolverAlgorithm::Bitboard,
+        ] {
+            let solutions: Vec<Sudoku> = sudoku.solutions(&algorithm).collect();
+            assert_eq!(solutions.len(), 2);
+            assert!(solutions
+                .iter()
+                .all(|solution| solution.check_sudoku_completed()));
+            assert_ne!(solutions[0], solutions[1]);
+        }
+        assert_eq!(sudoku.value(0, 0), '0');
+    }
+
+    #[test]
+    fn take_solutions_lazily() {
+        let sudoku = Sudoku::create_board("0".repeat(81)).unwrap();
+        let solutions: Vec<Sudoku> = sudoku
+            .solutions(&SolverAlgorithm::MostRestricted)
+            .take(20)
+            .collect();
+        assert_eq!(solutions.len(), 20);
+        for (index, solution) in solutions.iter().enumerate() {
+            assert!(solution.check_sudoku_completed());
+            assert!(!solutions[index + 1..].contains(solution));
+        }
+    }
+
+    #[test]
+    fn yield_filled_board_once() {
+        let sudoku = Sudoku::create_board(
+            "123456789\
+             456789123\
+             789123456\
+             234567891\
+             567891234\
+             891234567\
+             345678912\
+             678912345\
+             912345678"
+                .to_string(),
+        )
+        .unwrap();
+        for algorithm in [
+            SolverAlgorithm::Simple,
+            SolverAlgorithm::DancingLinks,
+            SolverAlgorithm::Sat,
+            SolverAlgorithm::Propagation,
+            SolverAlgorithm::Bitboard,
+        ] {
+            let mut solutions = sudoku.solutions(&algorithm);
+            assert_eq!(solutions.next(), Some(sudoku.clone()));
+            assert_eq!(solutions.next(), None);
+        }
+    }
+
+    #[test]
+    fn yield_nothing_for_unsolvable_sudoku() {
+        let sudoku = Sudoku::create_board(
+            "012345678\
+             900000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000\
+             000000000"
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(sudoku.solutions(&SolverAlgorithm::Simple).count(), 0);
+        assert_eq!(sudoku.solutions(&SolverAlgorithm::DancingLinks).count(), 0);
+        assert_eq!(sudoku.solutions(&SolverAlgorithm::Sat).count(), 0);
+    }
+}